@@ -46,7 +46,7 @@ echo Speak into your microphone
 echo ----------------------------------------
 echo.
 
-cargo run --release
+cargo run --release --features cuda
 
 echo.
 echo Done.
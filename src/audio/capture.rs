@@ -3,10 +3,15 @@ use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
 use cpal::{Device, SampleRate, Stream, StreamConfig};
 use log::{debug, info, warn};
 use std::io::{self, Write};
+use std::path::Path;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use thiserror::Error;
 
+use super::wav;
+use crate::config::{AudioConfig, InputBackend};
+
 /// リングバッファの容量（2秒分 @ 48kHz = 96000サンプル）
 /// デバイスレートが48kHzの場合でも十分な容量を確保
 const RING_BUFFER_CAPACITY: usize = 96000;
@@ -28,6 +33,9 @@ pub enum CaptureError {
 
     #[error("録音中にエラーが発生: {0}")]
     RecordingError(String),
+
+    #[error("入力WAVファイルの読み込みに失敗: {0}")]
+    InputFileError(String),
 }
 
 /// リングバッファの内部状態
@@ -35,8 +43,6 @@ struct AudioCaptureInner {
     ring_buffer: Vec<f32>,
     write_pos: usize,
     total_written: u64,
-    /// ストリーミング用の読み取り位置（total_written単位）
-    stream_read_pos: u64,
 }
 
 impl AudioCaptureInner {
@@ -45,7 +51,6 @@ impl AudioCaptureInner {
             ring_buffer: vec![0.0; RING_BUFFER_CAPACITY],
             write_pos: 0,
             total_written: 0,
-            stream_read_pos: 0,
         }
     }
 
@@ -78,19 +83,19 @@ impl AudioCaptureInner {
         result
     }
 
-    /// ストリーミング読み取り用: まだ読み取っていないサンプル数を返す
-    fn unread_samples(&self) -> usize {
-        if self.total_written <= self.stream_read_pos {
+    /// 指定した読み取り位置からの未読サンプル数を返す
+    fn unread_from(&self, read_pos: u64) -> usize {
+        if self.total_written <= read_pos {
             return 0;
         }
-        let unread = self.total_written - self.stream_read_pos;
+        let unread = self.total_written - read_pos;
         // リングバッファ容量を超えていたらデータロスト
         unread.min(RING_BUFFER_CAPACITY as u64) as usize
     }
 
-    /// ストリーミング読み取り: 連続した次のN個のサンプルを返す（重複なし）
-    fn read_stream(&mut self, num_samples: usize) -> Vec<f32> {
-        let available = self.unread_samples();
+    /// 指定した読み取り位置から連続した次のN個のサンプルを返し、位置を進める
+    fn read_from(&self, read_pos: &mut u64, num_samples: usize) -> Vec<f32> {
+        let available = self.unread_from(*read_pos);
         let to_read = num_samples.min(available);
 
         if to_read == 0 {
@@ -100,13 +105,13 @@ impl AudioCaptureInner {
         // 読み取り位置がオーバーライトされた場合、最古の有効位置にジャンプ
         if self.total_written > RING_BUFFER_CAPACITY as u64 {
             let oldest_valid = self.total_written - RING_BUFFER_CAPACITY as u64;
-            if self.stream_read_pos < oldest_valid {
-                self.stream_read_pos = oldest_valid;
+            if *read_pos < oldest_valid {
+                *read_pos = oldest_valid;
             }
         }
 
         // シンプルな計算: 論理位置をバッファインデックスに変換
-        let buffer_start = (self.stream_read_pos % RING_BUFFER_CAPACITY as u64) as usize;
+        let buffer_start = (*read_pos % RING_BUFFER_CAPACITY as u64) as usize;
 
         let mut result = Vec::with_capacity(to_read);
         for i in 0..to_read {
@@ -114,15 +119,10 @@ impl AudioCaptureInner {
         }
 
         // 読み取り位置を進める
-        self.stream_read_pos += to_read as u64;
+        *read_pos += to_read as u64;
 
         result
     }
-
-    /// ストリーミング読み取り位置をリセット（現在位置に同期）
-    fn reset_stream_position(&mut self) {
-        self.stream_read_pos = self.total_written;
-    }
 }
 
 /// 録音状態の管理
@@ -158,6 +158,8 @@ struct RecordingState {
     debounce_frames: usize,
     /// サンプルレート（デバッグログ用）
     sample_rate: u32,
+    /// 発話開始の期限（サンプル数、これまでに発話がなければ停止）
+    speech_deadline: Option<usize>,
 }
 
 impl RecordingState {
@@ -183,6 +185,7 @@ impl RecordingState {
             silent_frame_count: 0,
             debounce_frames: 3,
             sample_rate: 16000,
+            speech_deadline: None,
         }
     }
 
@@ -197,7 +200,9 @@ impl RecordingState {
         relative_threshold_multiplier: f32,
         calibration_duration: f32,
         debounce_frames: usize,
+        speech_timeout_samples: Option<usize>,
     ) {
+        self.speech_deadline = speech_timeout_samples.map(|timeout| lookback_samples.len() + timeout);
         self.samples = lookback_samples;
         self.is_recording = true;
         self.speech_detected = false;
@@ -304,19 +309,205 @@ impl RecordingState {
         if self.speech_detected && self.consecutive_silence >= self.silence_samples_threshold {
             return true;
         }
+        if !self.speech_detected && self.speech_deadline.is_some_and(|deadline| self.samples.len() >= deadline) {
+            return true;
+        }
         false
     }
 }
 
+/// 入力ストリームから共有バッファへサンプルを書き込む
+///
+/// cpalのコールバックとWAVファイル入力スレッドの両方から使用する。
+#[derive(Clone)]
+struct InputSink {
+    inner: Arc<Mutex<AudioCaptureInner>>,
+    recording_state: Arc<Mutex<RecordingState>>,
+    recording_active: Arc<AtomicBool>,
+    gain: f32,
+}
+
+impl InputSink {
+    /// インターリーブされた入力をモノラル化・ゲイン適用して書き込む
+    fn push(&self, data: &[f32], channels: usize) {
+        // マルチチャンネルをモノラルに変換し、ゲインを適用
+        let mono_samples: Vec<f32> = data
+            .chunks(channels)
+            .map(|chunk| {
+                let sample = chunk.iter().sum::<f32>() / channels as f32;
+                // ゲイン適用 & クリッピング防止
+                (sample * self.gain).clamp(-1.0, 1.0)
+            })
+            .collect();
+
+        // リングバッファに書き込み
+        {
+            let mut inner = self.inner.lock().unwrap();
+            inner.write_samples(&mono_samples);
+        }
+
+        // 録音中の場合は録音バッファにも追加
+        if self.recording_active.load(Ordering::Relaxed) {
+            let mut state = self.recording_state.lock().unwrap();
+            state.add_samples(&mono_samples);
+        }
+    }
+}
+
+/// 共有リングバッファを独立した読み取り位置で連続読み取りするリーダー
+///
+/// AudioCapture本体（cpalストリームを所有するためスレッド間で共有できない）とは別に、
+/// ウェイクワード検出スレッドなどへ渡して使用する。
+pub struct CaptureReader {
+    inner: Arc<Mutex<AudioCaptureInner>>,
+    read_pos: u64,
+    sample_rate: u32,
+    target_sample_rate: u32,
+    resample_ratio: f64,
+}
+
+impl CaptureReader {
+    /// 読み取り位置を現在位置に同期（過去のサンプルを読み飛ばす）
+    pub fn reset(&mut self) {
+        let inner = self.inner.lock().unwrap();
+        self.read_pos = inner.total_written;
+    }
+
+    /// 出力サンプルレート（[audio] sample_rate）
+    pub fn target_sample_rate(&self) -> u32 {
+        self.target_sample_rate
+    }
+
+    /// 連続した次のフレームをi16形式で返す（重複なし、データが揃うまで待機）
+    ///
+    /// num_samples: target_sample_rate (16kHz) でのサンプル数
+    pub fn read_frame(&mut self, num_samples: usize) -> Vec<i16> {
+        // デバイスレートでのサンプル数計算
+        let device_samples = (num_samples as f64 * self.resample_ratio).ceil() as usize;
+        let start = std::time::Instant::now();
+
+        // 必要なサンプル数が蓄積されるまで待機
+        loop {
+            let unread = {
+                let inner = self.inner.lock().unwrap();
+                inner.unread_from(self.read_pos)
+            };
+
+            if unread >= device_samples {
+                break;
+            }
+
+            if start.elapsed().as_secs() > 2 {
+                debug!(
+                    "read_frame timeout: unread={} required={}",
+                    unread, device_samples
+                );
+                // タイムアウト時は利用可能な分だけで進む
+                break;
+            }
+
+            std::thread::sleep(std::time::Duration::from_millis(5));
+        }
+
+        let samples = {
+            let inner = self.inner.lock().unwrap();
+            inner.read_from(&mut self.read_pos, device_samples)
+        };
+
+        to_i16_frame(samples, self.sample_rate, self.target_sample_rate, num_samples)
+    }
+}
+
+/// 録音中の音声の途中経過
+pub struct RecordingSnapshot {
+    /// これまでに録音した音声（target_sample_rate、lookback込み）
+    pub audio: Vec<f32>,
+    /// 発話を検出したか
+    pub speech_detected: bool,
+}
+
+/// 録音中の音声を別スレッドから参照するモニター（ストリーミング認識用）
+///
+/// CaptureReaderと同様、AudioCapture本体とは別にスレッドへ渡して使用する。
+#[derive(Clone)]
+pub struct RecordingMonitor {
+    recording_state: Arc<Mutex<RecordingState>>,
+    sample_rate: u32,
+    target_sample_rate: u32,
+}
+
+impl RecordingMonitor {
+    /// 録音中の音声を取得（録音中でなければNone）
+    pub fn snapshot(&self) -> Option<RecordingSnapshot> {
+        let (samples, speech_detected) = {
+            let state = self.recording_state.lock().unwrap();
+            if !state.is_recording {
+                return None;
+            }
+            (state.samples.clone(), state.speech_detected)
+        };
+
+        let audio = if self.sample_rate != self.target_sample_rate {
+            resample(&samples, self.sample_rate, self.target_sample_rate)
+        } else {
+            samples
+        };
+        Some(RecordingSnapshot { audio, speech_detected })
+    }
+
+    /// 直近の録音の末尾で続いていた無音の長さ（target_sample_rateのサンプル数）
+    ///
+    /// 録音終了後に呼ぶと、無音検出で停止した録音の発話終了位置を求められる。
+    pub fn trailing_silence(&self) -> usize {
+        let silence = self.recording_state.lock().unwrap().consecutive_silence;
+        (silence as f64 * self.target_sample_rate as f64 / self.sample_rate as f64) as usize
+    }
+}
+
+/// デバイスレートのf32サンプルをtarget_sample_rateのi16フレームに変換
+fn to_i16_frame(samples: Vec<f32>, sample_rate: u32, target_sample_rate: u32, num_samples: usize) -> Vec<i16> {
+    if samples.is_empty() {
+        return vec![0i16; num_samples];
+    }
+
+    // リサンプル
+    let resampled = if sample_rate != target_sample_rate {
+        resample(&samples, sample_rate, target_sample_rate)
+    } else {
+        samples
+    };
+
+    // f32 [-1.0, 1.0] を i16 に変換
+    let mut i16_samples: Vec<i16> = resampled.iter().map(|&s| wav::to_i16(s)).collect();
+
+    // サンプル数を正確に num_samples に合わせる
+    i16_samples.resize(num_samples, 0);
+
+    i16_samples
+}
+
+/// 音声入力のソース
+enum CaptureSource {
+    /// 実デバイス（cpal永続ストリーム）
+    Device {
+        #[allow(dead_code)]
+        device: Device,
+        #[allow(dead_code)]
+        config: StreamConfig,
+        _stream: Stream,
+    },
+    /// WAVファイルを実時間で流し込むスレッド（サウンドハードウェア不要）
+    File { running: Arc<AtomicBool> },
+}
+
+/// WAVファイル入力の1チャンクの長さ（ミリ秒）
+const FILE_INPUT_CHUNK_MS: u64 = 10;
+
 /// マイクからの音声キャプチャを管理（永続ストリーム版）
 pub struct AudioCapture {
-    #[allow(dead_code)]
-    device: Device,
-    #[allow(dead_code)]
-    config: StreamConfig,
+    source: CaptureSource,
     sample_rate: u32,
     target_sample_rate: u32,
-    _stream: Stream,
     inner: Arc<Mutex<AudioCaptureInner>>,
     recording_state: Arc<Mutex<RecordingState>>,
     recording_active: Arc<AtomicBool>,
@@ -393,11 +584,13 @@ impl AudioCapture {
         let recording_state = Arc::new(Mutex::new(RecordingState::new()));
         let recording_active = Arc::new(AtomicBool::new(false));
 
-        // コールバック用のクローン
-        let inner_clone = Arc::clone(&inner);
-        let recording_state_clone = Arc::clone(&recording_state);
-        let recording_active_clone = Arc::clone(&recording_active);
-        let gain = input_gain;
+        // コールバック用の書き込み口
+        let input_sink = InputSink {
+            inner: Arc::clone(&inner),
+            recording_state: Arc::clone(&recording_state),
+            recording_active: Arc::clone(&recording_active),
+            gain: input_gain,
+        };
 
         let err_flag = Arc::new(Mutex::new(None::<String>));
         let err_flag_clone = Arc::clone(&err_flag);
@@ -407,27 +600,7 @@ impl AudioCapture {
             .build_input_stream(
                 &config,
                 move |data: &[f32], _: &cpal::InputCallbackInfo| {
-                    // マルチチャンネルをモノラルに変換し、ゲインを適用
-                    let mono_samples: Vec<f32> = data
-                        .chunks(channels)
-                        .map(|chunk| {
-                            let sample = chunk.iter().sum::<f32>() / channels as f32;
-                            // ゲイン適用 & クリッピング防止
-                            (sample * gain).clamp(-1.0, 1.0)
-                        })
-                        .collect();
-
-                    // リングバッファに書き込み
-                    {
-                        let mut inner = inner_clone.lock().unwrap();
-                        inner.write_samples(&mono_samples);
-                    }
-
-                    // 録音中の場合は録音バッファにも追加
-                    if recording_active_clone.load(Ordering::Relaxed) {
-                        let mut state = recording_state_clone.lock().unwrap();
-                        state.add_samples(&mono_samples);
-                    }
+                    input_sink.push(data, channels);
                 },
                 move |err| {
                     let mut error = err_flag_clone.lock().unwrap();
@@ -445,11 +618,13 @@ impl AudioCapture {
         info!("永続オーディオストリームを開始しました");
 
         let capture = Self {
-            device,
-            config,
+            source: CaptureSource::Device {
+                device,
+                config,
+                _stream: stream,
+            },
             sample_rate,
             target_sample_rate,
-            _stream: stream,
             inner,
             recording_state,
             recording_active,
@@ -461,13 +636,140 @@ impl AudioCapture {
             debounce_frames,
         };
 
+        capture.wait_for_warmup();
+        Ok(capture)
+    }
+
+    /// WAVファイルを入力とするAudioCaptureを初期化（サウンドハードウェア不要）
+    ///
+    /// ファイルの内容を実時間のペースでバッファへ流し込み、
+    /// 終端に達した後は無音を供給し続ける。
+    pub fn from_wav_file<P: AsRef<Path>>(
+        path: P,
+        target_sample_rate: u32,
+        input_gain: f32,
+        smoothing_alpha: f32,
+        relative_threshold_multiplier: f32,
+        calibration_duration: f32,
+        debounce_frames: usize,
+    ) -> Result<Self> {
+        let path = path.as_ref();
+        let (samples, sample_rate) = wav::read_wav_mono(path)
+            .map_err(|e| CaptureError::InputFileError(format!("{}: {:#}", path.display(), e)))?;
+
+        info!(
+            "音声入力: WAVファイル {} ({}Hz, {:.2}秒, gain={:.1}x)",
+            path.display(),
+            sample_rate,
+            samples.len() as f32 / sample_rate as f32,
+            input_gain
+        );
+
+        let resample_ratio = sample_rate as f64 / target_sample_rate as f64;
+
+        let inner = Arc::new(Mutex::new(AudioCaptureInner::new()));
+        let recording_state = Arc::new(Mutex::new(RecordingState::new()));
+        let recording_active = Arc::new(AtomicBool::new(false));
+        let running = Arc::new(AtomicBool::new(true));
+
+        let input_sink = InputSink {
+            inner: Arc::clone(&inner),
+            recording_state: Arc::clone(&recording_state),
+            recording_active: Arc::clone(&recording_active),
+            gain: input_gain,
+        };
+        let running_clone = Arc::clone(&running);
+
+        std::thread::Builder::new()
+            .name("wav-input".to_string())
+            .spawn(move || {
+                let chunk_len = (sample_rate as u64 * FILE_INPUT_CHUNK_MS / 1000).max(1) as usize;
+                let silence = vec![0.0f32; chunk_len];
+                let mut chunks = samples.chunks(chunk_len);
+                let mut ended = false;
+                let start = Instant::now();
+                let mut sent_chunks: u32 = 0;
+
+                while running_clone.load(Ordering::Relaxed) {
+                    match chunks.next() {
+                        Some(chunk) => input_sink.push(chunk, 1),
+                        None => {
+                            if !ended {
+                                info!("入力WAVファイルの終端に到達しました（以降は無音を供給）");
+                                ended = true;
+                            }
+                            input_sink.push(&silence, 1);
+                        }
+                    }
+                    sent_chunks += 1;
+
+                    // 実時間のペースに合わせて待機（累積誤差を避けるため開始時刻基準）
+                    let due = start + Duration::from_millis(FILE_INPUT_CHUNK_MS) * sent_chunks;
+                    if let Some(wait) = due.checked_duration_since(Instant::now()) {
+                        std::thread::sleep(wait);
+                    }
+                }
+            })
+            .map_err(|e| CaptureError::StreamError(e.to_string()))?;
+
+        let capture = Self {
+            source: CaptureSource::File { running },
+            sample_rate,
+            target_sample_rate,
+            inner,
+            recording_state,
+            recording_active,
+            resample_ratio,
+            input_gain,
+            smoothing_alpha,
+            relative_threshold_multiplier,
+            calibration_duration,
+            debounce_frames,
+        };
+
+        capture.wait_for_warmup();
+        Ok(capture)
+    }
+
+    /// 設定で選択された入力バックエンドでAudioCaptureを初期化
+    pub fn from_config(config: &AudioConfig) -> Result<Self> {
+        match config.input_backend {
+            InputBackend::Device => Self::new(
+                config.sample_rate,
+                config.input_gain,
+                config.smoothing_alpha,
+                config.relative_threshold_multiplier,
+                config.calibration_duration,
+                config.debounce_frames,
+            ),
+            InputBackend::File => {
+                let path = config.input_file.as_deref().ok_or_else(|| {
+                    CaptureError::InputFileError("input_backend = \"file\" には input_file の指定が必要です".to_string())
+                })?;
+                Self::from_wav_file(
+                    path,
+                    config.sample_rate,
+                    config.input_gain,
+                    config.smoothing_alpha,
+                    config.relative_threshold_multiplier,
+                    config.calibration_duration,
+                    config.debounce_frames,
+                )
+            }
+        }
+    }
+
+    /// 入力のウォームアップを待ち、バッファをクリーンな状態にする
+    fn wait_for_warmup(&self) {
+        let sample_rate = self.sample_rate;
+
         // 初期化時にバッファが十分に蓄積されるまで待機
         // マイクのウォームアップ期間を考慮して2秒待機
         let warmup_samples = (sample_rate as f64 * 2.0) as u64; // 2秒分
         let start = std::time::Instant::now();
         loop {
             let written = {
-                let inner_guard = capture.inner.lock().unwrap();
+                let inner_guard = self.inner.lock().unwrap();
                 inner_guard.total_written
             };
             if written >= warmup_samples {
@@ -487,11 +789,10 @@ impl AudioCapture {
         // ウォームアップ後、バッファをクリアして新しいデータから開始
         // これにより起動時のノイズやポップ音による誤検出を防ぐ
         {
-            let mut inner_guard = capture.inner.lock().unwrap();
+            let mut inner_guard = self.inner.lock().unwrap();
             inner_guard.ring_buffer.fill(0.0);
             inner_guard.write_pos = 0;
             inner_guard.total_written = 0;
-            inner_guard.stream_read_pos = 0;
         }
         info!("オーディオバッファをクリアしました（クリーンスタート）");
 
@@ -500,7 +801,7 @@ impl AudioCapture {
         let start = std::time::Instant::now();
         loop {
             let written = {
-                let inner_guard = capture.inner.lock().unwrap();
+                let inner_guard = self.inner.lock().unwrap();
                 inner_guard.total_written
             };
             if written >= min_samples_needed {
@@ -516,8 +817,6 @@ impl AudioCapture {
             }
             std::thread::sleep(std::time::Duration::from_millis(20));
         }
-
-        Ok(capture)
     }
 
     /// リングバッファから最新のN個のサンプルを取得（ウェイクワード検出用）
@@ -565,70 +864,25 @@ impl AudioCapture {
         i16_samples
     }
 
-    /// 指定されたサンプル数を録音してi16形式で返す（ストリーミング用）
-    /// ウェイクワード検出に使用 - 連続したフレームを返す（重複なし）
-    pub fn record_samples(&self, num_samples: usize) -> Result<Vec<i16>> {
-        // デバイスレートでのサンプル数計算
-        let device_samples = (num_samples as f64 * self.resample_ratio).ceil() as usize;
-        let start = std::time::Instant::now();
-
-        // 必要なサンプル数が蓄積されるまで待機
-        loop {
-            let unread = {
-                let inner = self.inner.lock().unwrap();
-                inner.unread_samples()
-            };
-
-            if unread >= device_samples {
-                break;
-            }
-
-            if start.elapsed().as_secs() > 2 {
-                debug!(
-                    "record_samples timeout: unread={} required={}",
-                    unread, device_samples
-                );
-                // タイムアウト時は利用可能な分だけで進む
-                break;
-            }
-
-            std::thread::sleep(std::time::Duration::from_millis(5));
-        }
-
-        // ストリーミング読み取り（連続、重複なし）
-        let samples = {
-            let mut inner = self.inner.lock().unwrap();
-            inner.read_stream(device_samples)
-        };
-
-        if samples.is_empty() {
-            debug!("record_samples: no samples available");
-            return Ok(vec![0i16; num_samples]);
+    /// 独立した読み取り位置を持つリーダーを作成（現在位置から読み取り開始）
+    pub fn reader(&self) -> CaptureReader {
+        let read_pos = self.inner.lock().unwrap().total_written;
+        CaptureReader {
+            inner: Arc::clone(&self.inner),
+            read_pos,
+            sample_rate: self.sample_rate,
+            target_sample_rate: self.target_sample_rate,
+            resample_ratio: self.resample_ratio,
         }
-
-        // リサンプル
-        let resampled = if self.sample_rate != self.target_sample_rate {
-            resample(&samples, self.sample_rate, self.target_sample_rate)
-        } else {
-            samples
-        };
-
-        // f32 [-1.0, 1.0] を i16 に変換
-        let mut i16_samples: Vec<i16> = resampled
-            .iter()
-            .map(|&s| (s.clamp(-1.0, 1.0) * i16::MAX as f32) as i16)
-            .collect();
-
-        // サンプル数を正確に num_samples に合わせる
-        i16_samples.resize(num_samples, 0);
-
-        Ok(i16_samples)
     }
 
-    /// ストリーミング読み取り位置をリセット（現在位置に同期）
-    pub fn reset_stream_position(&self) {
-        let mut inner = self.inner.lock().unwrap();
-        inner.reset_stream_position();
+    /// 録音中の音声を参照するモニターを作成
+    pub fn recording_monitor(&self) -> RecordingMonitor {
+        RecordingMonitor {
+            recording_state: Arc::clone(&self.recording_state),
+            sample_rate: self.sample_rate,
+            target_sample_rate: self.target_sample_rate,
+        }
     }
 
     /// 録音を開始（lookback込み）
@@ -637,9 +891,11 @@ impl AudioCapture {
         max_duration_secs: f32,
         silence_threshold: f32,
         silence_duration_secs: f32,
+        speech_timeout_secs: Option<f32>,
     ) {
         let max_samples = (max_duration_secs * self.sample_rate as f32) as usize;
         let silence_samples = (silence_duration_secs * self.sample_rate as f32) as usize;
+        let speech_timeout_samples = speech_timeout_secs.map(|secs| (secs * self.sample_rate as f32) as usize);
 
         // lookbackサンプルをリングバッファから取得
         let lookback = {
@@ -661,6 +917,7 @@ impl AudioCapture {
                 self.relative_threshold_multiplier,
                 self.calibration_duration,
                 self.debounce_frames,
+                speech_timeout_samples,
             );
         }
 
@@ -704,7 +961,7 @@ impl AudioCapture {
         silence_threshold: f32,
         silence_duration_secs: f32,
     ) -> Result<Vec<f32>> {
-        self.record_internal(max_duration_secs, silence_threshold, silence_duration_secs, true)
+        self.record_internal(max_duration_secs, silence_threshold, silence_duration_secs, None, None, true)
     }
 
     /// 無音検出で自動停止する録音を実行（詳細表示モード - コマンド入力用）
@@ -714,7 +971,50 @@ impl AudioCapture {
         silence_threshold: f32,
         silence_duration_secs: f32,
     ) -> Result<Vec<f32>> {
-        self.record_internal(max_duration_secs, silence_threshold, silence_duration_secs, false)
+        self.record_internal(max_duration_secs, silence_threshold, silence_duration_secs, None, None, false)
+    }
+
+    /// 発話開始を待つ時間を区切って録音（フォローアップ用）
+    ///
+    /// `speech_timeout_secs`以内に発話が始まらなければNoneを返す。
+    pub fn record_follow_up(
+        &self,
+        max_duration_secs: f32,
+        silence_threshold: f32,
+        silence_duration_secs: f32,
+        speech_timeout_secs: f32,
+    ) -> Result<Option<Vec<f32>>> {
+        let samples = self.record_internal(
+            max_duration_secs,
+            silence_threshold,
+            silence_duration_secs,
+            Some(speech_timeout_secs),
+            None,
+            false,
+        )?;
+
+        let speech_detected = self.recording_state.lock().unwrap().speech_detected;
+        if !speech_detected {
+            debug!("フォローアップ: {:.1}秒以内に発話がありませんでした", speech_timeout_secs);
+            return Ok(None);
+        }
+        Ok(Some(samples))
+    }
+
+    /// 停止要求があるまで録音（プッシュトゥトーク用、無音では停止しない）
+    ///
+    /// 停止要求が来なくても`max_duration_secs`で必ず終了する。
+    ///
+    /// # Arguments
+    /// * `max_duration_secs` - 最大録音時間（秒）
+    /// * `stop` - 録音中に定期的に呼ばれ、trueを返すと録音を終了する
+    pub fn record_until_stopped(
+        &self,
+        max_duration_secs: f32,
+        mut stop: impl FnMut() -> bool,
+    ) -> Result<Vec<f32>> {
+        // 無音時間を最大録音時間と同じにして、無音による停止を無効にする
+        self.record_internal(max_duration_secs, 0.0, max_duration_secs, None, Some(&mut stop), false)
     }
 
     fn record_internal(
@@ -722,27 +1022,45 @@ impl AudioCapture {
         max_duration_secs: f32,
         silence_threshold: f32,
         silence_duration_secs: f32,
+        speech_timeout_secs: Option<f32>,
+        mut stop: Option<&mut dyn FnMut() -> bool>,
         quiet: bool,
     ) -> Result<Vec<f32>> {
         // 録音開始
-        self.start_recording(max_duration_secs, silence_threshold, silence_duration_secs);
+        self.start_recording(max_duration_secs, silence_threshold, silence_duration_secs, speech_timeout_secs);
 
         if !quiet {
             println!();
-            println!(
-                ">>> Recording... (max {}s, silence {}s to stop)",
-                max_duration_secs, silence_duration_secs
-            );
+            if stop.is_some() {
+                println!(">>> Recording... (max {}s, release/press again to stop)", max_duration_secs);
+            } else {
+                println!(
+                    ">>> Recording... (max {}s, silence {}s to stop)",
+                    max_duration_secs, silence_duration_secs
+                );
+            }
             println!(">>> Speak now!");
             println!();
         }
 
         let mut last_print = std::time::Instant::now();
+        // 入力が途切れてサンプル数の上限に届かない場合でも、最大録音時間で打ち切る
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs_f32(max_duration_secs);
 
         // 録音完了を待機
         while !self.is_recording_complete() {
             std::thread::sleep(std::time::Duration::from_millis(50));
 
+            if std::time::Instant::now() >= deadline {
+                debug!("最大録音時間に達したため録音を終了します");
+                break;
+            }
+
+            if stop.as_mut().is_some_and(|stop| stop()) {
+                debug!("録音停止が要求されました");
+                break;
+            }
+
             if !quiet && last_print.elapsed().as_millis() >= 100 {
                 let (level, speech_detected) = self.get_current_level();
                 let bars = (level * 50.0).min(50.0) as usize;
@@ -779,7 +1097,15 @@ impl AudioCapture {
     }
 }
 
-fn resample(samples: &[f32], from_rate: u32, to_rate: u32) -> Vec<f32> {
+impl Drop for AudioCapture {
+    fn drop(&mut self) {
+        if let CaptureSource::File { running } = &self.source {
+            running.store(false, Ordering::Relaxed);
+        }
+    }
+}
+
+pub(crate) fn resample(samples: &[f32], from_rate: u32, to_rate: u32) -> Vec<f32> {
     if from_rate == to_rate {
         return samples.to_vec();
     }
@@ -0,0 +1,44 @@
+use anyhow::Result;
+
+use super::wav;
+
+/// チャイムのサンプルレート
+const CHIME_SAMPLE_RATE: u32 = 24000;
+/// 1音の長さ（秒）
+const CHIME_NOTE_SECONDS: f32 = 0.12;
+/// フェードイン/アウトの長さ（秒、クリックノイズ防止）
+const CHIME_FADE_SECONDS: f32 = 0.01;
+/// フォローアップ待機のチャイム（ド→ミの上昇2音、Hz）
+const FOLLOW_UP_NOTES: [f32; 2] = [1046.5, 1318.5];
+
+/// フォローアップ待機開始を知らせる控えめなチャイムを生成（WAVバイト列）
+///
+/// # Arguments
+/// * `volume` - 音量（0.0〜1.0）
+pub fn follow_up_chime(volume: f32) -> Result<Vec<u8>> {
+    let samples = tone_sequence(&FOLLOW_UP_NOTES, volume.clamp(0.0, 1.0));
+    wav::encode_wav_i16(&samples, CHIME_SAMPLE_RATE)
+}
+
+/// サイン波の音列を生成（各音にフェードを付けて減衰させる）
+fn tone_sequence(frequencies: &[f32], volume: f32) -> Vec<f32> {
+    let note_len = (CHIME_NOTE_SECONDS * CHIME_SAMPLE_RATE as f32) as usize;
+    let fade_len = (CHIME_FADE_SECONDS * CHIME_SAMPLE_RATE as f32) as usize;
+
+    let mut samples = Vec::with_capacity(note_len * frequencies.len());
+    for &frequency in frequencies {
+        for i in 0..note_len {
+            let t = i as f32 / CHIME_SAMPLE_RATE as f32;
+            let envelope = if i < fade_len {
+                i as f32 / fade_len as f32
+            } else {
+                // 残りは指数減衰（ベルのような音）
+                (-((i - fade_len) as f32) / note_len as f32 * 3.0).exp()
+            };
+            let fade_out = ((note_len - i) as f32 / fade_len as f32).min(1.0);
+            let value = (2.0 * std::f32::consts::PI * frequency * t).sin();
+            samples.push(value * envelope * fade_out * volume);
+        }
+    }
+    samples
+}
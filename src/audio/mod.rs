@@ -1,5 +1,8 @@
 mod capture;
+pub mod chime;
 mod playback;
+pub mod wav;
 
-pub use capture::AudioCapture;
-pub use playback::AudioPlayback;
+pub(crate) use capture::resample;
+pub use capture::{AudioCapture, CaptureReader, RecordingMonitor};
+pub use playback::{AudioPlayback, SpeakingState};
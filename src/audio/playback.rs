@@ -1,9 +1,17 @@
 use anyhow::Result;
-use log::{debug, info};
+use hound::{Sample, SampleFormat, WavReader, WavSpec, WavWriter};
+use log::{debug, info, warn};
 use rodio::{Decoder, OutputStream, OutputStreamHandle, Sink};
 use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use thiserror::Error;
 
+use super::wav;
+use crate::cancel::CancelToken;
+use crate::config::{AudioConfig, FileOutputMode, OutputBackend};
+
 /// 音声再生に関するエラー
 #[derive(Debug, Error)]
 pub enum PlaybackError {
@@ -15,12 +23,199 @@ pub enum PlaybackError {
 
     #[error("再生中にエラーが発生: {0}")]
     PlayError(String),
+
+    #[error("WAVファイルへの出力に失敗: {0}")]
+    FileOutputError(String),
+}
+
+/// 中断可能な再生でキャンセルを確認する間隔
+const CANCEL_POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// 1回分の発話（再生）区間
+#[derive(Debug, Clone, Copy)]
+pub struct SpeakingSpan {
+    /// 再生開始時刻
+    pub started_at: Instant,
+    /// 再生終了時刻（再生中は予定時刻）
+    pub ends_at: Instant,
+}
+
+/// スピーカーから発話中かどうかを他スレッドへ公開する共有状態
+///
+/// 自分の応答音声によるウェイクワード誤検出の抑制に使う。
+/// 実デバイスへの再生のみ記録する（ファイル・nullバックエンドは音が出ないため対象外）。
+#[derive(Debug, Clone, Default)]
+pub struct SpeakingState {
+    span: Arc<Mutex<Option<SpeakingSpan>>>,
+}
+
+impl SpeakingState {
+    /// 直近の発話区間
+    pub fn last_span(&self) -> Option<SpeakingSpan> {
+        *self.span.lock().unwrap()
+    }
+
+    /// 発話中、または発話終了から`holdoff`以内か
+    pub fn is_speaking_within(&self, holdoff: Duration) -> bool {
+        let now = Instant::now();
+        self.last_span()
+            .is_some_and(|span| now >= span.started_at && now < span.ends_at + holdoff)
+    }
+
+    /// 発話区間の開始を記録
+    fn begin(&self, started_at: Instant, duration: Duration) {
+        *self.span.lock().unwrap() = Some(SpeakingSpan {
+            started_at,
+            ends_at: started_at + duration,
+        });
+    }
+
+    /// 発話区間の終了時刻を実際の値に更新
+    fn finish(&self) {
+        if let Some(span) = self.span.lock().unwrap().as_mut() {
+            span.ends_at = Instant::now();
+        }
+    }
+}
+
+/// WAVファイル出力先の状態
+struct FileSink {
+    /// 出力先（append: WAVファイルパス、rotate: ディレクトリ）
+    path: PathBuf,
+    mode: FileOutputMode,
+    /// rotateモードの連番（前回の実行で書き出したファイルの続きから）
+    counter: u32,
+}
+
+impl FileSink {
+    fn new(path: PathBuf, mode: FileOutputMode) -> Result<Self> {
+        match mode {
+            FileOutputMode::Append => {
+                if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+                    std::fs::create_dir_all(parent)
+                        .map_err(|e| PlaybackError::FileOutputError(format!("{}: {}", parent.display(), e)))?;
+                }
+            }
+            FileOutputMode::Rotate => {
+                std::fs::create_dir_all(&path)
+                    .map_err(|e| PlaybackError::FileOutputError(format!("{}: {}", path.display(), e)))?;
+            }
+        }
+        let counter = match mode {
+            FileOutputMode::Append => 0,
+            FileOutputMode::Rotate => Self::last_reply_index(&path)?,
+        };
+        Ok(Self { path, mode, counter })
+    }
+
+    /// ディレクトリ内の既存の`reply_NNNN.wav`の最大番号（なければ0）
+    fn last_reply_index(dir: &Path) -> Result<u32> {
+        let entries = std::fs::read_dir(dir)
+            .map_err(|e| PlaybackError::FileOutputError(format!("{}: {}", dir.display(), e)))?;
+        let last = entries
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                let name = entry.file_name();
+                name.to_str()?
+                    .strip_prefix("reply_")?
+                    .strip_suffix(".wav")?
+                    .parse::<u32>()
+                    .ok()
+            })
+            .max()
+            .unwrap_or(0);
+        if last > 0 {
+            debug!("既存の出力ファイルの続きから書き出します: reply_{:04}.wav以降", last + 1);
+        }
+        Ok(last)
+    }
+
+    /// 応答1件分のWAVを書き出し、書き出し先のパスを返す
+    fn write(&mut self, wav_data: &[u8]) -> Result<PathBuf> {
+        match self.mode {
+            FileOutputMode::Rotate => {
+                self.counter += 1;
+                let path = self.path.join(format!("reply_{:04}.wav", self.counter));
+                std::fs::write(&path, wav_data)
+                    .map_err(|e| PlaybackError::FileOutputError(format!("{}: {}", path.display(), e)))?;
+                Ok(path)
+            }
+            FileOutputMode::Append => {
+                let mut reader = WavReader::new(Cursor::new(wav_data))
+                    .map_err(|e| PlaybackError::DecodeError(e.to_string()))?;
+                let spec = reader.spec();
+                match spec.sample_format {
+                    SampleFormat::Int => {
+                        let samples = read_samples::<i32>(&mut reader)?;
+                        self.append(spec, samples)
+                    }
+                    SampleFormat::Float => {
+                        let samples = read_samples::<f32>(&mut reader)?;
+                        self.append(spec, samples)
+                    }
+                }
+            }
+        }
+    }
+
+    /// appendモード: 既存のWAVファイルの末尾にサンプルを追記（なければ新規作成）
+    fn append<S: Sample>(&self, spec: WavSpec, samples: Vec<S>) -> Result<PathBuf> {
+        let path = self.path.clone();
+        let mut writer = if path.exists() {
+            let writer = WavWriter::append(&path)
+                .map_err(|e| PlaybackError::FileOutputError(format!("{}: {}", path.display(), e)))?;
+            if writer.spec() != spec {
+                return Err(PlaybackError::FileOutputError(format!(
+                    "既存ファイルとWAV形式が異なるため追記できません: {} ({:?} != {:?})",
+                    path.display(),
+                    writer.spec(),
+                    spec
+                ))
+                .into());
+            }
+            writer
+        } else {
+            WavWriter::create(&path, spec)
+                .map_err(|e| PlaybackError::FileOutputError(format!("{}: {}", path.display(), e)))?
+        };
+
+        for sample in samples {
+            writer
+                .write_sample(sample)
+                .map_err(|e| PlaybackError::FileOutputError(e.to_string()))?;
+        }
+        writer
+            .finalize()
+            .map_err(|e| PlaybackError::FileOutputError(e.to_string()))?;
+        Ok(path)
+    }
+}
+
+/// WAVの全サンプルを指定した型で読み出す
+fn read_samples<S: Sample>(reader: &mut WavReader<Cursor<&[u8]>>) -> Result<Vec<S>> {
+    Ok(reader
+        .samples::<S>()
+        .collect::<Result<_, _>>()
+        .map_err(|e| PlaybackError::DecodeError(e.to_string()))?)
+}
+
+/// 出力バックエンド
+enum Backend {
+    /// 実デバイス（rodio）
+    Device {
+        _stream: OutputStream,
+        handle: OutputStreamHandle,
+    },
+    /// WAVファイル書き出し
+    File(Mutex<FileSink>),
+    /// 再生せず、再生時間だけ待機する
+    Null,
 }
 
 /// スピーカーへの音声再生を管理
 pub struct AudioPlayback {
-    _stream: OutputStream,
-    handle: OutputStreamHandle,
+    backend: Backend,
+    speaking: SpeakingState,
 }
 
 impl AudioPlayback {
@@ -31,52 +226,162 @@ impl AudioPlayback {
 
         info!("音声再生デバイスを初期化しました");
 
-        Ok(Self {
+        Ok(Self::with_backend(Backend::Device {
             _stream: stream,
             handle,
-        })
+        }))
+    }
+
+    /// 設定で選択された出力バックエンドでAudioPlaybackを初期化
+    pub fn from_config(config: &AudioConfig) -> Result<Self> {
+        match config.output_backend {
+            OutputBackend::Device => Self::new(),
+            OutputBackend::File => {
+                let sink = FileSink::new(PathBuf::from(config.output_path()), config.output_file_mode)?;
+                info!(
+                    "音声出力: WAVファイル ({:?}, {})",
+                    config.output_file_mode,
+                    config.output_path()
+                );
+                Ok(Self::with_backend(Backend::File(Mutex::new(sink))))
+            }
+            OutputBackend::Null => {
+                info!("音声出力: nullシンク（再生せず再生時間だけ待機）");
+                Ok(Self::with_backend(Backend::Null))
+            }
+        }
+    }
+
+    fn with_backend(backend: Backend) -> Self {
+        Self {
+            backend,
+            speaking: SpeakingState::default(),
+        }
+    }
+
+    /// 発話中の状態（ウェイクワード検出スレッドと共有する）
+    pub fn speaking_state(&self) -> SpeakingState {
+        self.speaking.clone()
     }
 
     /// WAV形式の音声データを再生（再生完了まで待機）
     ///
+    /// ファイルバックエンドでは書き出しのみ行い待機しない。
+    /// nullバックエンドは実デバイスと同じタイミングになるよう再生時間だけ待機する。
+    ///
     /// # Arguments
     /// * `wav_data` - WAV形式の音声データ（バイト列）
     pub fn play_wav(&self, wav_data: &[u8]) -> Result<()> {
-        debug!("WAV再生開始: {} bytes", wav_data.len());
-
-        let cursor = Cursor::new(wav_data.to_vec());
-        let source = Decoder::new(cursor)
-            .map_err(|e| PlaybackError::DecodeError(e.to_string()))?;
-
-        let sink = Sink::try_new(&self.handle)
-            .map_err(|e| PlaybackError::PlayError(e.to_string()))?;
-
-        sink.append(source);
-        sink.sleep_until_end();
-
-        debug!("WAV再生完了");
-        Ok(())
+        self.play_internal(wav_data, None).map(|_| ())
     }
 
-    /// WAV形式の音声データを非同期で再生（待機なし）
+    /// WAV形式の音声データを中断可能に再生（再生完了またはキャンセルまで待機）
     ///
     /// # Arguments
     /// * `wav_data` - WAV形式の音声データ（バイト列）
+    /// * `cancel` - キャンセルされたら再生を止める
     ///
     /// # Returns
-    /// 再生を制御するためのSink
-    pub fn play_wav_async(&self, wav_data: &[u8]) -> Result<Sink> {
-        debug!("WAV非同期再生開始: {} bytes", wav_data.len());
+    /// 最後まで再生した場合true、キャンセルで中断した場合false
+    pub fn play_wav_cancellable(&self, wav_data: &[u8], cancel: &CancelToken) -> Result<bool> {
+        self.play_internal(wav_data, Some(cancel))
+    }
+
+    fn play_internal(&self, wav_data: &[u8], cancel: Option<&CancelToken>) -> Result<bool> {
+        debug!("WAV再生開始: {} bytes", wav_data.len());
+        let started_at = Instant::now();
+
+        match &self.backend {
+            Backend::Device { handle, .. } => {
+                let cursor = Cursor::new(wav_data.to_vec());
+                let source = Decoder::new(cursor)
+                    .map_err(|e| PlaybackError::DecodeError(e.to_string()))?;
 
-        let cursor = Cursor::new(wav_data.to_vec());
-        let source = Decoder::new(cursor)
-            .map_err(|e| PlaybackError::DecodeError(e.to_string()))?;
+                let sink = Sink::try_new(handle)
+                    .map_err(|e| PlaybackError::PlayError(e.to_string()))?;
 
-        let sink = Sink::try_new(&self.handle)
-            .map_err(|e| PlaybackError::PlayError(e.to_string()))?;
+                sink.append(source);
+                self.record_timing(started_at, wav_data);
+                let completed = match cancel {
+                    Some(cancel) => loop {
+                        if sink.empty() {
+                            break true;
+                        }
+                        if cancel.is_cancelled() {
+                            sink.stop();
+                            break false;
+                        }
+                        std::thread::sleep(CANCEL_POLL_INTERVAL);
+                    },
+                    None => {
+                        sink.sleep_until_end();
+                        true
+                    }
+                };
+                self.speaking.finish();
 
-        sink.append(source);
+                if !completed {
+                    info!("再生を中断しました（{:.2}秒）", started_at.elapsed().as_secs_f32());
+                    return Ok(false);
+                }
+            }
+            Backend::File(sink) => {
+                let path = sink.lock().unwrap().write(wav_data)?;
+                info!("応答をWAVファイルに出力: {}", path.display());
+                self.record_timing(started_at, wav_data);
+            }
+            Backend::Null => {
+                let duration = self.record_timing(started_at, wav_data);
+                if !wait_unless_cancelled(duration, cancel) {
+                    info!("再生を中断しました（{:.2}秒）", started_at.elapsed().as_secs_f32());
+                    return Ok(false);
+                }
+            }
+        }
+
+        debug!("WAV再生完了");
+        Ok(true)
+    }
+
+    /// 再生タイミングを記録し、再生時間を返す
+    fn record_timing(&self, started_at: Instant, wav_data: &[u8]) -> Duration {
+        let duration = match wav::wav_duration_secs(wav_data) {
+            Ok(secs) => Duration::from_secs_f32(secs),
+            Err(e) => {
+                warn!("再生時間の取得に失敗: {}", e);
+                started_at.elapsed()
+            }
+        };
+
+        match self.backend {
+            Backend::Device { .. } => self.speaking.begin(started_at, duration),
+            Backend::Null => {
+                info!("nullシンク: {:.2}秒の応答を破棄 ({} bytes)", duration.as_secs_f32(), wav_data.len());
+            }
+            Backend::File(_) => {}
+        }
+        duration
+    }
+}
 
-        Ok(sink)
+/// `duration`だけ待機する（キャンセルされたら途中で戻る）
+///
+/// # Returns
+/// 最後まで待機した場合true、キャンセルで中断した場合false
+fn wait_unless_cancelled(duration: Duration, cancel: Option<&CancelToken>) -> bool {
+    let Some(cancel) = cancel else {
+        std::thread::sleep(duration);
+        return true;
+    };
+    let deadline = Instant::now() + duration;
+    loop {
+        if cancel.is_cancelled() {
+            return false;
+        }
+        let now = Instant::now();
+        if now >= deadline {
+            return true;
+        }
+        std::thread::sleep(CANCEL_POLL_INTERVAL.min(deadline - now));
     }
 }
@@ -0,0 +1,110 @@
+use anyhow::{Context, Result};
+use hound::{SampleFormat, WavReader, WavSpec, WavWriter};
+use std::io::Cursor;
+use std::path::Path;
+
+use super::capture::resample;
+
+/// WAVファイルを読み込み、モノラルf32（-1.0〜1.0）に変換して返す
+///
+/// マルチチャンネルはチャンネル平均でモノラル化する。
+///
+/// # Returns
+/// (サンプル列, ファイルのサンプルレート)
+pub fn read_wav_mono<P: AsRef<Path>>(path: P) -> Result<(Vec<f32>, u32)> {
+    let path = path.as_ref();
+    let reader = WavReader::open(path)
+        .with_context(|| format!("WAVファイルを開けません: {}", path.display()))?;
+    decode_reader(reader).with_context(|| format!("WAVファイルのデコードに失敗: {}", path.display()))
+}
+
+/// WAVファイルを読み込み、指定サンプルレートのモノラルf32に変換して返す
+pub fn read_wav_resampled<P: AsRef<Path>>(path: P, target_sample_rate: u32) -> Result<Vec<f32>> {
+    let (samples, sample_rate) = read_wav_mono(path)?;
+    Ok(resample(&samples, sample_rate, target_sample_rate))
+}
+
+/// WAV形式のバイト列をモノラルf32に変換して返す
+///
+/// # Returns
+/// (サンプル列, サンプルレート)
+pub fn decode_wav_bytes(wav_data: &[u8]) -> Result<(Vec<f32>, u32)> {
+    let reader = WavReader::new(Cursor::new(wav_data)).context("WAVヘッダーの解析に失敗")?;
+    decode_reader(reader)
+}
+
+/// WAV形式のバイト列の再生時間（秒）を返す（サンプルはデコードしない）
+pub fn wav_duration_secs(wav_data: &[u8]) -> Result<f32> {
+    let reader = WavReader::new(Cursor::new(wav_data)).context("WAVヘッダーの解析に失敗")?;
+    let sample_rate = reader.spec().sample_rate;
+    Ok(reader.duration() as f32 / sample_rate as f32)
+}
+
+/// モノラルf32サンプルを16bit PCMのWAVファイルとして保存
+pub fn write_wav_i16<P: AsRef<Path>>(path: P, samples: &[f32], sample_rate: u32) -> Result<()> {
+    let path = path.as_ref();
+    let mut writer = WavWriter::create(path, mono_i16_spec(sample_rate))
+        .with_context(|| format!("WAVファイルを作成できません: {}", path.display()))?;
+    for &sample in samples {
+        writer.write_sample(to_i16(sample))?;
+    }
+    writer
+        .finalize()
+        .with_context(|| format!("WAVファイルの書き込みに失敗: {}", path.display()))?;
+    Ok(())
+}
+
+/// モノラルf32サンプルを16bit PCMのWAVバイト列にエンコード
+pub fn encode_wav_i16(samples: &[f32], sample_rate: u32) -> Result<Vec<u8>> {
+    let mut buffer = Cursor::new(Vec::new());
+    {
+        let mut writer = WavWriter::new(&mut buffer, mono_i16_spec(sample_rate))?;
+        for &sample in samples {
+            writer.write_sample(to_i16(sample))?;
+        }
+        writer.finalize()?;
+    }
+    Ok(buffer.into_inner())
+}
+
+/// 16bit PCM・モノラルのWAV仕様
+pub fn mono_i16_spec(sample_rate: u32) -> WavSpec {
+    WavSpec {
+        channels: 1,
+        sample_rate,
+        bits_per_sample: 16,
+        sample_format: SampleFormat::Int,
+    }
+}
+
+/// f32 [-1.0, 1.0] を i16 に変換
+pub fn to_i16(sample: f32) -> i16 {
+    (sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16
+}
+
+fn decode_reader<R: std::io::Read>(mut reader: WavReader<R>) -> Result<(Vec<f32>, u32)> {
+    let spec = reader.spec();
+    let channels = spec.channels.max(1) as usize;
+
+    let interleaved: Vec<f32> = match spec.sample_format {
+        SampleFormat::Float => reader.samples::<f32>().collect::<Result<_, _>>()?,
+        SampleFormat::Int => {
+            let scale = (1i64 << (spec.bits_per_sample - 1)) as f32;
+            reader
+                .samples::<i32>()
+                .map(|s| s.map(|s| s as f32 / scale))
+                .collect::<Result<_, _>>()?
+        }
+    };
+
+    let mono = if channels == 1 {
+        interleaved
+    } else {
+        interleaved
+            .chunks(channels)
+            .map(|chunk| chunk.iter().sum::<f32>() / channels as f32)
+            .collect()
+    };
+
+    Ok((mono, spec.sample_rate))
+}
@@ -0,0 +1,64 @@
+use anyhow::{bail, Result};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// 処理の完了を待つ間にキャンセルを確認する間隔
+const CANCEL_POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// スレッド間で共有するキャンセル要求
+///
+/// ウェイクワードによる割り込み（バージイン）で、応答の生成・再生を中断するのに使う。
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// キャンセルを要求
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// キャンセルが要求されているか
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// 処理をワーカースレッドで実行し、完了するかキャンセルされるまで待つ
+    ///
+    /// キャンセルされたら処理の完了を待たずにNoneを返す（処理の結果は破棄される）。
+    /// HTTPリクエストなど中断できない処理の途中でも、すぐに次の録音へ移れるようにする。
+    ///
+    /// # Arguments
+    /// * `name` - ワーカースレッド名
+    /// * `task` - 実行する処理
+    pub fn run<T, F>(&self, name: &str, task: F) -> Result<Option<T>>
+    where
+        T: Send + 'static,
+        F: FnOnce() -> Result<T> + Send + 'static,
+    {
+        let (sender, receiver) = mpsc::channel();
+        std::thread::Builder::new().name(name.to_string()).spawn(move || {
+            // 待機側がキャンセルで戻った後は受信者がいないため、送信失敗は無視する
+            let _ = sender.send(task());
+        })?;
+
+        loop {
+            match receiver.recv_timeout(CANCEL_POLL_INTERVAL) {
+                Ok(result) => return result.map(Some),
+                Err(RecvTimeoutError::Timeout) => {
+                    if self.is_cancelled() {
+                        return Ok(None);
+                    }
+                }
+                Err(RecvTimeoutError::Disconnected) => bail!("{}スレッドが異常終了しました", name),
+            }
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use anyhow::Result;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+/// サブコマンド用の簡易引数パーサー
+///
+/// `--key value` 形式のオプション（同じキーの繰り返し・複数値可）と、
+/// 値を取らないフラグを扱う。
+pub struct Args {
+    options: HashMap<String, Vec<String>>,
+    flags: Vec<String>,
+}
+
+impl Args {
+    /// 引数列をパース
+    ///
+    /// # Arguments
+    /// * `args` - サブコマンド名より後ろの引数
+    /// * `flag_names` - 値を取らないフラグ名（`--`なし）
+    pub fn parse(args: &[String], flag_names: &[&str]) -> Result<Self> {
+        let mut options: HashMap<String, Vec<String>> = HashMap::new();
+        let mut flags = Vec::new();
+
+        let mut iter = args.iter().peekable();
+        while let Some(arg) = iter.next() {
+            let Some(name) = arg.strip_prefix("--") else {
+                return Err(anyhow::anyhow!("不明な引数です: {}", arg));
+            };
+
+            if flag_names.contains(&name) {
+                flags.push(name.to_string());
+                continue;
+            }
+
+            // 値を1つ以上取る（次の--オプションまで）
+            let mut values = Vec::new();
+            while let Some(next) = iter.peek() {
+                if next.starts_with("--") {
+                    break;
+                }
+                values.push(iter.next().unwrap().clone());
+            }
+            if values.is_empty() {
+                return Err(anyhow::anyhow!("オプション --{} に値がありません", name));
+            }
+            options.entry(name.to_string()).or_default().extend(values);
+        }
+
+        Ok(Self { options, flags })
+    }
+
+    /// オプションの値（複数指定時は最後の値）
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.options
+            .get(name)
+            .and_then(|values| values.last())
+            .map(String::as_str)
+    }
+
+    /// オプションの全ての値
+    pub fn get_all(&self, name: &str) -> Vec<String> {
+        self.options.get(name).cloned().unwrap_or_default()
+    }
+
+    /// 必須オプションの値
+    pub fn require(&self, name: &str) -> Result<&str> {
+        self.get(name)
+            .ok_or_else(|| anyhow::anyhow!("オプション --{} は必須です", name))
+    }
+
+    /// オプションの値をパース（未指定時はデフォルト値）
+    pub fn parse_or<T: FromStr>(&self, name: &str, default: T) -> Result<T>
+    where
+        T::Err: std::fmt::Display,
+    {
+        match self.get(name) {
+            Some(value) => value
+                .parse()
+                .map_err(|e| anyhow::anyhow!("オプション --{} の値が不正です: {} ({})", name, value, e)),
+            None => Ok(default),
+        }
+    }
+
+    /// フラグが指定されているか
+    pub fn has_flag(&self, name: &str) -> bool {
+        self.flags.iter().any(|f| f == name)
+    }
+}
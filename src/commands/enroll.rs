@@ -0,0 +1,314 @@
+use anyhow::{Context, Result};
+use log::{info, warn};
+use rustpotter::{WakewordRef, WakewordRefBuildFromFiles, WakewordSave};
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+
+use super::args::Args;
+use crate::audio::{wav, AudioCapture};
+use crate::config::{Config, WakewordEngineKind, WakewordEntry};
+use crate::wakeword::{self, WakewordService};
+
+/// 1テイクの最大録音時間（秒）
+const TAKE_MAX_SECONDS: f32 = 3.0;
+/// 1テイクの録音終了とみなす無音時間（秒）
+const TAKE_SILENCE_SECONDS: f32 = 0.6;
+/// 品質チェック不合格時の1テイクあたりの再試行回数
+const MAX_RETRIES_PER_TAKE: usize = 3;
+
+// === テイク品質チェック設定 ===
+/// 発話区間判定のフレームサイズ（20ms @ 16kHz）
+const QUALITY_FRAME_SIZE: usize = 320;
+/// 発話フレームとみなすRMS（ピーク比）
+const QUALITY_SPEECH_RATIO: f32 = 0.1;
+/// 発話区間の最短長（秒）
+const QUALITY_MIN_SPEECH_SECONDS: f32 = 0.3;
+/// 発話区間の最長長（秒、ウェイクワードとしては長すぎる）
+const QUALITY_MAX_SPEECH_SECONDS: f32 = 2.0;
+/// 最低ピーク振幅（これ未満は音量不足）
+const QUALITY_MIN_PEAK: f32 = 0.05;
+/// クリッピングとみなす振幅
+const QUALITY_CLIP_LEVEL: f32 = 0.99;
+/// 許容するクリッピングサンプルの割合
+const QUALITY_MAX_CLIP_RATIO: f32 = 0.01;
+/// 発話区間とその外側の最低RMS比（S/N）
+const QUALITY_MIN_SNR: f32 = 3.0;
+/// トリミング時に発話区間の前後に残すマージン（秒）
+const TRIM_MARGIN_SECONDS: f32 = 0.1;
+
+const USAGE: &str = "\
+Usage: smart_speaker enroll --name <NAME> [options]
+
+Options:
+  --name <NAME>            ウェイクワード名（検出時のkeyword）
+  --output <PATH>          出力する.rpwファイル（デフォルト: <NAME>.rpw）
+  --samples <N>            マイクから録音するサンプル数（デフォルト: 5）
+  --samples-dir <DIR>      録音したサンプルの保存先（デフォルト: enroll/<NAME>）
+  --wav <FILE>...          録音せず既存のWAVファイルから作成
+  --threshold <F>          モデルに埋め込む検出閾値（省略時は[wakeword]の値を使用）
+  --avg-threshold <F>      モデルに埋め込む平均スコア閾値
+  --mfcc-size <N>          MFCC係数の数（デフォルト: 16）
+  --test                   作成後にそのままライブ検出テストを行う";
+
+/// 1テイクの品質チェック結果
+struct TakeQuality {
+    /// 発話区間（サンプル位置）
+    speech_start: usize,
+    speech_end: usize,
+    peak: f32,
+    snr: f32,
+}
+
+/// `enroll` サブコマンド: ローカル録音から.rpwウェイクワードを作成
+pub fn run(config: &Config, args: &[String]) -> Result<()> {
+    if args.iter().any(|a| a == "--help") {
+        println!("{}", USAGE);
+        return Ok(());
+    }
+
+    let args = Args::parse(args, &["test"])?;
+    let name = args.require("name")?.to_string();
+    let output = args
+        .get("output")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(format!("{}.rpw", name)));
+    let num_samples: usize = args.parse_or("samples", 5)?;
+    let samples_dir = args
+        .get("samples-dir")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| Path::new("enroll").join(&name));
+    let threshold: f32 = args.parse_or("threshold", config.wakeword.threshold)?;
+    let avg_threshold: f32 = args.parse_or("avg-threshold", config.wakeword.avg_threshold)?;
+    let mfcc_size: u16 = args.parse_or("mfcc-size", 16)?;
+
+    let sample_files = {
+        let wav_files = args.get_all("wav");
+        if wav_files.is_empty() {
+            record_samples(config, &name, num_samples, &samples_dir)?
+        } else {
+            info!("既存のWAVファイルからウェイクワードを作成: {} 件", wav_files.len());
+            for file in &wav_files {
+                check_wav_file(file, config.audio.sample_rate);
+            }
+            wav_files
+        }
+    };
+
+    if sample_files.len() < 2 {
+        return Err(anyhow::anyhow!(
+            "ウェイクワードの作成には2件以上のサンプルが必要です（{} 件）",
+            sample_files.len()
+        ));
+    }
+
+    info!(
+        "ウェイクワード作成中: name=\"{}\", samples={}, threshold={}, avg_threshold={}, mfcc_size={}",
+        name,
+        sample_files.len(),
+        threshold,
+        avg_threshold,
+        mfcc_size
+    );
+
+    let wakeword = WakewordRef::new_from_sample_files(
+        name.clone(),
+        Some(threshold),
+        Some(avg_threshold),
+        sample_files,
+        mfcc_size,
+    )
+    .map_err(|e| anyhow::anyhow!("ウェイクワードの作成に失敗: {}", e))?;
+
+    let output_str = output.to_string_lossy().to_string();
+    wakeword
+        .save_to_file(&output_str)
+        .map_err(|e| anyhow::anyhow!("ウェイクワードファイルの保存に失敗: {} - {}", output_str, e))?;
+
+    println!();
+    println!(">>> Wakeword model saved: {}", output.display());
+
+    if args.has_flag("test") {
+        live_test(config, &name, &output_str)?;
+    }
+
+    Ok(())
+}
+
+/// マイクからサンプルを録音し、品質チェックに合格したテイクをWAVで保存
+fn record_samples(config: &Config, name: &str, num_samples: usize, samples_dir: &Path) -> Result<Vec<String>> {
+    std::fs::create_dir_all(samples_dir)
+        .with_context(|| format!("サンプル保存先を作成できません: {}", samples_dir.display()))?;
+
+    let capture = AudioCapture::from_config(&config.audio)?;
+    let sample_rate = config.audio.sample_rate;
+    let stdin = io::stdin();
+
+    let mut files = Vec::with_capacity(num_samples);
+    for take in 1..=num_samples {
+        let mut accepted = false;
+
+        for attempt in 1..=MAX_RETRIES_PER_TAKE {
+            println!();
+            println!("========================================");
+            println!("  Take {}/{} (attempt {}/{})", take, num_samples, attempt, MAX_RETRIES_PER_TAKE);
+            println!("  Press Enter, then say \"{}\" once.", name);
+            println!("========================================");
+            let _ = io::stdout().flush();
+            let mut line = String::new();
+            stdin.lock().read_line(&mut line)?;
+
+            let audio = capture.record_with_feedback(
+                TAKE_MAX_SECONDS,
+                config.audio.silence_threshold,
+                TAKE_SILENCE_SECONDS,
+            )?;
+
+            match check_take(&audio, sample_rate) {
+                Ok(quality) => {
+                    let margin = (TRIM_MARGIN_SECONDS * sample_rate as f32) as usize;
+                    let start = quality.speech_start.saturating_sub(margin);
+                    let end = (quality.speech_end + margin).min(audio.len());
+
+                    let path = samples_dir.join(format!("{}_{:02}.wav", name, take));
+                    wav::write_wav_i16(&path, &audio[start..end], sample_rate)?;
+
+                    println!(
+                        ">>> OK: {:.2}s speech, peak {:.2}, S/N {:.1} -> {}",
+                        (quality.speech_end - quality.speech_start) as f32 / sample_rate as f32,
+                        quality.peak,
+                        quality.snr,
+                        path.display()
+                    );
+                    files.push(path.to_string_lossy().to_string());
+                    accepted = true;
+                    break;
+                }
+                Err(reason) => {
+                    println!(">>> Rejected: {}", reason);
+                }
+            }
+        }
+
+        if !accepted {
+            warn!("テイク{}は品質チェックに合格しませんでした（スキップ）", take);
+        }
+    }
+
+    Ok(files)
+}
+
+/// テイクの品質チェック
+///
+/// 発話区間の長さ、音量、クリッピング、S/N比を確認する。
+fn check_take(audio: &[f32], sample_rate: u32) -> std::result::Result<TakeQuality, String> {
+    if audio.len() < QUALITY_FRAME_SIZE {
+        return Err("録音が短すぎます".to_string());
+    }
+
+    let peak = audio.iter().fold(0.0_f32, |a, &b| a.max(b.abs()));
+    if peak < QUALITY_MIN_PEAK {
+        return Err(format!("音量が小さすぎます (peak={:.3})", peak));
+    }
+
+    let clipped = audio.iter().filter(|s| s.abs() >= QUALITY_CLIP_LEVEL).count();
+    let clip_ratio = clipped as f32 / audio.len() as f32;
+    if clip_ratio > QUALITY_MAX_CLIP_RATIO {
+        return Err(format!(
+            "音割れしています (clipping {:.1}%)。マイクから少し離れてください",
+            clip_ratio * 100.0
+        ));
+    }
+
+    // フレームRMSから発話区間を推定
+    let frame_rms: Vec<f32> = audio
+        .chunks_exact(QUALITY_FRAME_SIZE)
+        .map(|frame| (frame.iter().map(|s| s * s).sum::<f32>() / frame.len() as f32).sqrt())
+        .collect();
+    let speech_level = peak * QUALITY_SPEECH_RATIO;
+    let first = frame_rms.iter().position(|&rms| rms >= speech_level);
+    let last = frame_rms.iter().rposition(|&rms| rms >= speech_level);
+    let (Some(first), Some(last)) = (first, last) else {
+        return Err("発話が検出されませんでした".to_string());
+    };
+
+    let speech_start = first * QUALITY_FRAME_SIZE;
+    let speech_end = (last + 1) * QUALITY_FRAME_SIZE;
+    let speech_seconds = (speech_end - speech_start) as f32 / sample_rate as f32;
+    if speech_seconds < QUALITY_MIN_SPEECH_SECONDS {
+        return Err(format!("発話が短すぎます ({:.2}秒)", speech_seconds));
+    }
+    if speech_seconds > QUALITY_MAX_SPEECH_SECONDS {
+        return Err(format!(
+            "発話が長すぎます ({:.2}秒)。ウェイクワードだけを1回言ってください",
+            speech_seconds
+        ));
+    }
+
+    // S/N比: 発話区間と区間外（無音部）のRMS比
+    let mean = |values: &[f32]| values.iter().sum::<f32>() / values.len().max(1) as f32;
+    let speech_rms = mean(&frame_rms[first..=last]);
+    let noise_frames: Vec<f32> = frame_rms[..first]
+        .iter()
+        .chain(frame_rms[last + 1..].iter())
+        .copied()
+        .collect();
+    let snr = if noise_frames.is_empty() {
+        f32::INFINITY
+    } else {
+        speech_rms / mean(&noise_frames).max(1e-6)
+    };
+    if snr < QUALITY_MIN_SNR {
+        return Err(format!("周囲の雑音が大きすぎます (S/N {:.1})", snr));
+    }
+
+    Ok(TakeQuality {
+        speech_start,
+        speech_end,
+        peak,
+        snr,
+    })
+}
+
+/// 指定されたWAVファイルの品質を確認（不合格でも警告のみ）
+fn check_wav_file(path: &str, sample_rate: u32) {
+    match wav::read_wav_resampled(path, sample_rate) {
+        Ok(audio) => {
+            if let Err(reason) = check_take(&audio, sample_rate) {
+                warn!("サンプルの品質に問題があります: {} - {}", path, reason);
+            }
+        }
+        Err(e) => warn!("サンプルを読み込めません: {} - {:#}", path, e),
+    }
+}
+
+/// 作成したウェイクワードでライブ検出テスト（Ctrl+Cで終了）
+fn live_test(config: &Config, name: &str, model_path: &str) -> Result<()> {
+    let mut wakeword_config = config.wakeword.clone();
+    // 作成した.rpwモデルを試すため、常にRustpotterで検出する
+    wakeword_config.engine = WakewordEngineKind::Rustpotter;
+    wakeword_config.wakeword_path = None;
+    wakeword_config.keywords = vec![WakewordEntry {
+        path: Some(model_path.to_string()),
+        name: Some(name.to_string()),
+        profile: None,
+        phrases: Vec::new(),
+    }];
+
+    let detector = wakeword::create_engine(&wakeword_config, &config.stt)?;
+    let capture = AudioCapture::from_config(&config.audio)?;
+    let service = WakewordService::spawn(detector, capture.reader(), None)?;
+
+    println!();
+    println!(">>> Live test: say \"{}\" (Ctrl+C to quit)", name);
+
+    let mut detections = 0usize;
+    loop {
+        let event = service.recv()?;
+        detections += 1;
+        println!();
+        println!(
+            ">>> Detection #{}: \"{}\" (score: {:.3})",
+            detections, event.keyword, event.score
+        );
+    }
+}
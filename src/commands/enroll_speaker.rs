@@ -0,0 +1,162 @@
+use anyhow::Result;
+use log::{info, warn};
+use std::io::{self, BufRead, Write};
+
+use super::args::Args;
+use crate::audio::AudioCapture;
+use crate::config::Config;
+use crate::speaker::{create_extractor, extract_from_wav, EmbeddingExtractor, SpeakerProfile, SpeakerStore};
+
+/// 1発話の最大録音時間（秒）
+const UTTERANCE_MAX_SECONDS: f32 = 8.0;
+
+/// 登録時に読み上げてもらう例文（声の特徴を広く拾うため音素の異なる文を用意）
+const ENROLL_SENTENCES: &[&str] = &[
+    "今日の天気を教えてください。",
+    "明日の朝七時にアラームをセットして。",
+    "最近のニュースを簡単にまとめてくれる？",
+    "冷蔵庫にある材料で作れる料理を考えて。",
+    "週末におすすめの出かけ先はどこかな。",
+];
+
+const USAGE: &str = "\
+Usage: smart_speaker enroll-speaker --id <ID> [options]
+       smart_speaker enroll-speaker --list
+       smart_speaker enroll-speaker --remove --id <ID>
+
+Options:
+  --id <ID>                ユーザーID（LLMプロンプトに使用）
+  --name <NAME>            表示名（デフォルト: ID）
+  --samples <N>            マイクから録音する発話数（デフォルト: 3）
+  --wav <FILE>...          録音せず既存のWAVファイルから登録
+  --hints <TEXT>...        LLMのプロンプトに添える補足（例: 子ども 買い物は確認が必要）
+  --list                   登録済みの話者を表示
+  --remove                 指定したIDの話者を削除";
+
+/// `enroll-speaker` サブコマンド: 話者プロファイルを登録
+pub fn run(config: &Config, args: &[String]) -> Result<()> {
+    if args.iter().any(|a| a == "--help") {
+        println!("{}", USAGE);
+        return Ok(());
+    }
+
+    let args = Args::parse(args, &["list", "remove"])?;
+    let mut store = SpeakerStore::load(&config.speaker.profiles_path)?;
+
+    if args.has_flag("list") {
+        println!("Enrolled speakers ({}):", config.speaker.profiles_path);
+        for speaker in store.speakers() {
+            println!(
+                "  {} ({}) samples={} extractor={} hints={:?}",
+                speaker.id,
+                speaker.name,
+                speaker.embeddings.len(),
+                speaker.extractor,
+                speaker.prompt_hints
+            );
+        }
+        return Ok(());
+    }
+
+    let id = args.require("id")?.to_string();
+
+    if args.has_flag("remove") {
+        if !store.remove(&id) {
+            return Err(anyhow::anyhow!("話者が登録されていません: {}", id));
+        }
+        store.save()?;
+        println!(">>> Speaker removed: {}", id);
+        return Ok(());
+    }
+
+    let name = args.get("name").unwrap_or(id.as_str()).to_string();
+    let num_samples: usize = args.parse_or("samples", 3)?;
+    let prompt_hints = args.get_all("hints");
+    let extractor = create_extractor(&config.speaker.extractor)?;
+
+    let wav_files = args.get_all("wav");
+    let embeddings = if wav_files.is_empty() {
+        record_embeddings(config, extractor.as_ref(), &name, num_samples)?
+    } else {
+        let mut embeddings = Vec::with_capacity(wav_files.len());
+        for file in &wav_files {
+            match extract_from_wav(extractor.as_ref(), file) {
+                Ok(embedding) => embeddings.push(embedding),
+                Err(e) => warn!("サンプルをスキップ: {} - {:#}", file, e),
+            }
+        }
+        embeddings
+    };
+
+    if embeddings.is_empty() {
+        return Err(anyhow::anyhow!("登録に使える発話がありませんでした"));
+    }
+
+    info!(
+        "話者登録: id={}, name={}, samples={}, extractor={}",
+        id,
+        name,
+        embeddings.len(),
+        extractor.name()
+    );
+
+    store.upsert(SpeakerProfile {
+        id: id.clone(),
+        name,
+        prompt_hints,
+        extractor: extractor.name().to_string(),
+        embeddings,
+    });
+    store.save()?;
+
+    println!();
+    println!(">>> Speaker enrolled: {} -> {}", id, config.speaker.profiles_path);
+    if !config.speaker.enabled {
+        println!("    (set [speaker] enabled = true in settings.toml to use it)");
+    }
+    Ok(())
+}
+
+/// マイクから発話を録音して埋め込みを抽出
+fn record_embeddings(
+    config: &Config,
+    extractor: &dyn EmbeddingExtractor,
+    name: &str,
+    num_samples: usize,
+) -> Result<Vec<Vec<f32>>> {
+    let capture = AudioCapture::from_config(&config.audio)?;
+    let stdin = io::stdin();
+
+    let mut embeddings = Vec::with_capacity(num_samples);
+    for take in 1..=num_samples {
+        let sentence = ENROLL_SENTENCES[(take - 1) % ENROLL_SENTENCES.len()];
+        println!();
+        println!("========================================");
+        println!("  {}: utterance {}/{}", name, take, num_samples);
+        println!("  Press Enter, then read aloud:");
+        println!("    「{}」", sentence);
+        println!("========================================");
+        let _ = io::stdout().flush();
+        let mut line = String::new();
+        stdin.lock().read_line(&mut line)?;
+
+        let audio = capture.record_with_feedback(
+            UTTERANCE_MAX_SECONDS,
+            config.audio.silence_threshold,
+            config.audio.silence_duration,
+        )?;
+
+        match extractor.extract(&audio, config.audio.sample_rate) {
+            Ok(embedding) => {
+                println!(">>> OK ({:.1}s)", audio.len() as f32 / config.audio.sample_rate as f32);
+                embeddings.push(embedding);
+            }
+            Err(e) => {
+                println!(">>> Rejected: {}", e);
+                warn!("発話{}をスキップ: {:#}", take, e);
+            }
+        }
+    }
+
+    Ok(embeddings)
+}
@@ -0,0 +1,355 @@
+use anyhow::{Context, Result};
+use log::info;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use super::args::Args;
+use crate::audio::wav;
+use crate::config::{Config, SttConfig, WakewordConfig, WakewordEngineKind};
+use crate::wakeword::{self, WakewordEngine};
+
+/// 評価時の入力サンプルレート（検出エンジンは16kHz固定）
+const EVAL_SAMPLE_RATE: u32 = 16000;
+/// 各ポジティブクリップの前後に挿入する無音（秒）
+const POSITIVE_PADDING_SECONDS: f32 = 1.0;
+/// ストリーム先頭に流す無音（秒、Rustpotter内部状態の初期化用）
+const LEAD_IN_SECONDS: f32 = 1.0;
+
+const DEFAULT_THRESHOLDS: [f32; 8] = [0.25, 0.3, 0.35, 0.4, 0.45, 0.5, 0.55, 0.6];
+const DEFAULT_AVG_THRESHOLDS: [f32; 4] = [0.0, 0.1, 0.15, 0.2];
+const DEFAULT_MIN_SCORES: [usize; 4] = [1, 2, 3, 5];
+/// whisperエンジンでスイープするmin_similarity
+const DEFAULT_SIMILARITIES: [f32; 4] = [0.6, 0.7, 0.8, 0.9];
+
+const USAGE: &str = "\
+Usage: smart_speaker eval-wakeword --positive <DIR> --negative <DIR> [options]
+
+Options:
+  --positive <DIR>           ウェイクワードを1回ずつ含むWAVクリップのディレクトリ
+  --negative <DIR>           ウェイクワードを含まない長時間録音（TV・会話など）のディレクトリ
+  --engine <NAME>            検出エンジン（rustpotter, whisper、デフォルト: [wakeword] engine）
+  --thresholds <F>...        スイープするthreshold（カンマ/空白区切り、whisperではmin_similarity）
+  --avg-thresholds <F>...    スイープするavg_threshold（rustpotterのみ）
+  --min-scores <N>...        スイープするmin_scores（rustpotterのみ）
+  --max-fa-per-hour <F>      推奨設定の誤検出上限（回/時、デフォルト: 1.0）
+  --csv <PATH>               DETカーブのCSV出力先（デフォルト: wakeword_det.csv）";
+
+/// 1つのパラメータ組み合わせの評価結果
+struct EvalResult {
+    engine: WakewordEngineKind,
+    /// rustpotter: threshold、whisper: min_similarity
+    threshold: f32,
+    avg_threshold: f32,
+    min_scores: usize,
+    hits: usize,
+    positives: usize,
+    false_accepts: usize,
+    negative_hours: f32,
+}
+
+impl EvalResult {
+    fn miss_rate(&self) -> f32 {
+        if self.positives == 0 {
+            return 0.0;
+        }
+        1.0 - self.hits as f32 / self.positives as f32
+    }
+
+    fn fa_per_hour(&self) -> f32 {
+        if self.negative_hours <= 0.0 {
+            return 0.0;
+        }
+        self.false_accepts as f32 / self.negative_hours
+    }
+}
+
+/// `eval-wakeword` サブコマンド: ラベル付きコーパスで誤検出率・未検出率を評価
+pub fn run(config: &Config, args: &[String]) -> Result<()> {
+    if args.iter().any(|a| a == "--help") {
+        println!("{}", USAGE);
+        return Ok(());
+    }
+
+    let args = Args::parse(args, &[])?;
+    let positive_dir = PathBuf::from(args.require("positive")?);
+    let negative_dir = PathBuf::from(args.require("negative")?);
+    let engine = match args.get("engine") {
+        None => config.wakeword.engine,
+        Some("rustpotter") => WakewordEngineKind::Rustpotter,
+        Some("whisper") => WakewordEngineKind::Whisper,
+        Some(other) => {
+            return Err(anyhow::anyhow!("不明なエンジンです: {} (rustpotter, whisper)", other));
+        }
+    };
+    // whisperエンジンはmin_similarityのみをスイープする
+    let (thresholds, avg_thresholds, min_scores_list) = match engine {
+        WakewordEngineKind::Rustpotter => (
+            parse_list(&args.get_all("thresholds"), &DEFAULT_THRESHOLDS)?,
+            parse_list(&args.get_all("avg-thresholds"), &DEFAULT_AVG_THRESHOLDS)?,
+            parse_list(&args.get_all("min-scores"), &DEFAULT_MIN_SCORES)?,
+        ),
+        WakewordEngineKind::Whisper => (
+            parse_list(&args.get_all("thresholds"), &DEFAULT_SIMILARITIES)?,
+            vec![config.wakeword.avg_threshold],
+            vec![config.wakeword.min_scores],
+        ),
+    };
+    let max_fa_per_hour: f32 = args.parse_or("max-fa-per-hour", 1.0)?;
+    let csv_path = PathBuf::from(args.get("csv").unwrap_or("wakeword_det.csv"));
+
+    let positives = load_clips(&positive_dir)?;
+    let negatives = load_clips(&negative_dir)?;
+    if positives.is_empty() {
+        return Err(anyhow::anyhow!("ポジティブクリップがありません: {}", positive_dir.display()));
+    }
+
+    let negative_seconds: f32 = negatives
+        .iter()
+        .map(|(_, audio)| audio.len() as f32 / EVAL_SAMPLE_RATE as f32)
+        .sum();
+    info!(
+        "コーパス読み込み完了: positive={} 件, negative={} 件 ({:.2}時間)",
+        positives.len(),
+        negatives.len(),
+        negative_seconds / 3600.0
+    );
+
+    let total = thresholds.len() * avg_thresholds.len() * min_scores_list.len();
+    let mut results = Vec::with_capacity(total);
+    for &threshold in &thresholds {
+        for &avg_threshold in &avg_thresholds {
+            for &min_scores in &min_scores_list {
+                let mut wakeword_config = config.wakeword.clone();
+                wakeword_config.engine = engine;
+                match engine {
+                    WakewordEngineKind::Rustpotter => wakeword_config.threshold = threshold,
+                    WakewordEngineKind::Whisper => wakeword_config.whisper_spotter.min_similarity = threshold,
+                }
+                wakeword_config.avg_threshold = avg_threshold;
+                wakeword_config.min_scores = min_scores;
+                // 時刻・環境に依存する感度調整は評価では使わない
+                wakeword_config.schedules.clear();
+                wakeword_config.auto_sensitivity.enabled = false;
+
+                let result = evaluate(&wakeword_config, &config.stt, &positives, &negatives, negative_seconds)?;
+                println!(
+                    "[{}/{}] threshold={:.2} avg_threshold={:.2} min_scores={} -> miss {:.1}% ({}/{}), FA {:.2}/h ({})",
+                    results.len() + 1,
+                    total,
+                    threshold,
+                    avg_threshold,
+                    min_scores,
+                    result.miss_rate() * 100.0,
+                    result.positives - result.hits,
+                    result.positives,
+                    result.fa_per_hour(),
+                    result.false_accepts
+                );
+                results.push(result);
+            }
+        }
+    }
+
+    write_det_csv(&csv_path, &results)?;
+    println!();
+    println!(">>> DET curve written: {}", csv_path.display());
+
+    report_recommendation(&results, max_fa_per_hour);
+    Ok(())
+}
+
+/// 1つのパラメータ組み合わせでコーパス全体を評価
+fn evaluate(
+    wakeword_config: &WakewordConfig,
+    stt_config: &SttConfig,
+    positives: &[(PathBuf, Vec<i16>)],
+    negatives: &[(PathBuf, Vec<i16>)],
+    negative_seconds: f32,
+) -> Result<EvalResult> {
+    // ポジティブ: 無音を挟んで連結した1本のストリームとして処理し、
+    // 各クリップの区間内で検出があればヒットとする
+    let mut detector = wakeword::create_engine(wakeword_config, stt_config)?;
+    let frame_size = detector.get_samples_per_frame();
+    let padding = vec![0i16; (POSITIVE_PADDING_SECONDS * EVAL_SAMPLE_RATE as f32) as usize];
+    let lead_in = vec![0i16; (LEAD_IN_SECONDS * EVAL_SAMPLE_RATE as f32) as usize];
+    run_stream(detector.as_mut(), &lead_in, frame_size);
+
+    let mut hits = 0usize;
+    for (_, clip) in positives {
+        let mut stream = Vec::with_capacity(clip.len() + padding.len() * 2);
+        stream.extend_from_slice(&padding);
+        stream.extend_from_slice(clip);
+        stream.extend_from_slice(&padding);
+        if run_stream(detector.as_mut(), &stream, frame_size) > 0 {
+            hits += 1;
+        }
+    }
+
+    // ネガティブ: 全ての検出を誤検出として数える
+    let mut detector = wakeword::create_engine(wakeword_config, stt_config)?;
+    run_stream(detector.as_mut(), &lead_in, frame_size);
+    let false_accepts = negatives
+        .iter()
+        .map(|(_, audio)| run_stream(detector.as_mut(), audio, frame_size))
+        .sum();
+
+    let threshold = match wakeword_config.engine {
+        WakewordEngineKind::Rustpotter => wakeword_config.threshold,
+        WakewordEngineKind::Whisper => wakeword_config.whisper_spotter.min_similarity,
+    };
+    Ok(EvalResult {
+        engine: wakeword_config.engine,
+        threshold,
+        avg_threshold: wakeword_config.avg_threshold,
+        min_scores: wakeword_config.min_scores,
+        hits,
+        positives: positives.len(),
+        false_accepts,
+        negative_hours: negative_seconds / 3600.0,
+    })
+}
+
+/// 音声をフレーム単位で検出器に流し、検出回数を返す
+fn run_stream(detector: &mut dyn WakewordEngine, audio: &[i16], frame_size: usize) -> usize {
+    let mut detections = 0;
+    for frame in audio.chunks(frame_size) {
+        let frame = if frame.len() == frame_size {
+            frame.to_vec()
+        } else {
+            let mut padded = frame.to_vec();
+            padded.resize(frame_size, 0);
+            padded
+        };
+        if detector.process_frame(&frame).detection.is_some() {
+            detections += 1;
+        }
+    }
+    detections
+}
+
+/// ディレクトリ内のWAVファイルを16kHz/i16で読み込む（ファイル名順）
+fn load_clips(dir: &Path) -> Result<Vec<(PathBuf, Vec<i16>)>> {
+    let mut paths: Vec<PathBuf> = fs::read_dir(dir)
+        .with_context(|| format!("ディレクトリを開けません: {}", dir.display()))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            path.extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("wav"))
+        })
+        .collect();
+    paths.sort();
+
+    paths
+        .into_iter()
+        .map(|path| {
+            let audio = wav::read_wav_resampled(&path, EVAL_SAMPLE_RATE)?;
+            let samples = audio.iter().map(|&s| wav::to_i16(s)).collect();
+            Ok((path, samples))
+        })
+        .collect()
+}
+
+/// DETカーブ（誤検出率 vs 未検出率）をCSVで出力
+fn write_det_csv(path: &Path, results: &[EvalResult]) -> Result<()> {
+    let mut file = fs::File::create(path)
+        .with_context(|| format!("CSVファイルを作成できません: {}", path.display()))?;
+    writeln!(
+        file,
+        "engine,threshold,avg_threshold,min_scores,false_accepts,fa_per_hour,misses,positives,miss_rate"
+    )?;
+
+    let mut sorted: Vec<&EvalResult> = results.iter().collect();
+    sorted.sort_by(|a, b| a.fa_per_hour().total_cmp(&b.fa_per_hour()));
+    for r in sorted {
+        writeln!(
+            file,
+            "{:?},{:.3},{:.3},{},{},{:.4},{},{},{:.4}",
+            r.engine,
+            r.threshold,
+            r.avg_threshold,
+            r.min_scores,
+            r.false_accepts,
+            r.fa_per_hour(),
+            r.positives - r.hits,
+            r.positives,
+            r.miss_rate()
+        )?;
+    }
+    Ok(())
+}
+
+/// 誤検出上限を満たす中で未検出率が最小の設定を推奨として表示
+fn report_recommendation(results: &[EvalResult], max_fa_per_hour: f32) {
+    let by_miss_then_fa = |a: &&EvalResult, b: &&EvalResult| {
+        a.miss_rate()
+            .total_cmp(&b.miss_rate())
+            .then(a.fa_per_hour().total_cmp(&b.fa_per_hour()))
+    };
+
+    let within_target = results
+        .iter()
+        .filter(|r| r.fa_per_hour() <= max_fa_per_hour)
+        .min_by(by_miss_then_fa);
+
+    println!();
+    println!("========================================");
+    match within_target {
+        Some(best) => {
+            println!("  Recommended settings (FA <= {:.2}/h)", max_fa_per_hour);
+            print_settings(best);
+        }
+        None => {
+            println!("  No setting meets FA <= {:.2}/h", max_fa_per_hour);
+            if let Some(lowest_fa) = results
+                .iter()
+                .min_by(|a, b| a.fa_per_hour().total_cmp(&b.fa_per_hour()).then(a.miss_rate().total_cmp(&b.miss_rate())))
+            {
+                println!("  Lowest false-accept setting:");
+                print_settings(lowest_fa);
+            }
+        }
+    }
+    println!("========================================");
+}
+
+fn print_settings(r: &EvalResult) {
+    match r.engine {
+        WakewordEngineKind::Rustpotter => {
+            println!("    threshold = {:.2}", r.threshold);
+            println!("    avg_threshold = {:.2}", r.avg_threshold);
+            println!("    min_scores = {}", r.min_scores);
+        }
+        WakewordEngineKind::Whisper => {
+            println!("    [wakeword.whisper_spotter]");
+            println!("    min_similarity = {:.2}", r.threshold);
+        }
+    }
+    println!(
+        "  miss rate: {:.1}%, false accepts: {:.2}/h",
+        r.miss_rate() * 100.0,
+        r.fa_per_hour()
+    );
+}
+
+/// カンマ/空白区切りの値リストをパース（未指定時はデフォルト値）
+fn parse_list<T>(values: &[String], default: &[T]) -> Result<Vec<T>>
+where
+    T: std::str::FromStr + Clone,
+    T::Err: std::fmt::Display,
+{
+    if values.is_empty() {
+        return Ok(default.to_vec());
+    }
+    values
+        .iter()
+        .flat_map(|v| v.split(','))
+        .filter(|v| !v.trim().is_empty())
+        .map(|v| {
+            v.trim()
+                .parse()
+                .map_err(|e| anyhow::anyhow!("値が不正です: {} ({})", v, e))
+        })
+        .collect()
+}
@@ -0,0 +1,5 @@
+mod args;
+pub mod enroll;
+pub mod enroll_speaker;
+pub mod eval_wakeword;
+pub mod serve_stt;
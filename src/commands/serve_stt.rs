@@ -0,0 +1,281 @@
+use anyhow::{Context, Result};
+use log::{debug, info, warn};
+use serde_json::json;
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::time::{Duration, Instant};
+
+use super::args::Args;
+use crate::audio::{resample, wav};
+use crate::config::Config;
+use crate::stt::{Transcript, WhisperStt};
+
+/// Whisperの入力サンプルレート
+const STT_SAMPLE_RATE: u32 = 16000;
+/// リクエスト読み取りのタイムアウト
+const HTTP_READ_TIMEOUT: Duration = Duration::from_secs(10);
+/// 受け付ける最大のリクエストボディ（バイト）
+const MAX_BODY_BYTES: usize = 50 * 1024 * 1024;
+
+const USAGE: &str = "\
+Usage: smart_speaker serve-stt [options]
+
+Options:
+  --addr <ADDR>            待ち受けアドレス（デフォルト: 127.0.0.1:8178）
+
+POST /v1/audio/transcriptions (multipart/form-data, OpenAI互換)
+  file                     WAVファイル（16kHz以外はリサンプリング）
+  prompt                   初期プロンプト（省略可）
+  language                 認識言語（省略時は自動判定）
+  temperature              初期temperature 0.0〜1.0（省略時は[stt]の設定値）
+  response_format          json（デフォルト）, verbose_json, text";
+
+/// `serve-stt` サブコマンド: [stt] のWhisperモデルでOpenAI互換の文字起こしAPIを提供
+///
+/// `[stt] backend = "http"` の動作確認や、複数台で1つのモデルを共有するための簡易サーバー。
+/// リクエストは1件ずつ処理する。ハルシネーション除去・語彙補正はクライアント側で行うため、
+/// ここではWhisperの認識結果をそのまま返す。
+pub fn run(config: &Config, args: &[String]) -> Result<()> {
+    if args.iter().any(|a| a == "--help") {
+        println!("{}", USAGE);
+        return Ok(());
+    }
+
+    let args = Args::parse(args, &[])?;
+    let addr = args.get("addr").unwrap_or("127.0.0.1:8178");
+
+    let stt = WhisperStt::new(&config.stt)?;
+    let listener = TcpListener::bind(addr).with_context(|| format!("文字起こしサーバーを開始できません: {}", addr))?;
+    println!("Serving transcription API on http://{}/v1/audio/transcriptions", addr);
+
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(e) => {
+                warn!("文字起こしサーバー: 接続エラー: {}", e);
+                continue;
+            }
+        };
+        if let Err(e) = handle_connection(&stt, stream) {
+            warn!("文字起こしサーバー: リクエスト処理エラー: {:#}", e);
+        }
+    }
+    Ok(())
+}
+
+/// HTTPリクエスト
+struct Request {
+    method: String,
+    path: String,
+    content_type: String,
+    body: Vec<u8>,
+}
+
+/// 1リクエストを処理
+fn handle_connection(stt: &WhisperStt, mut stream: TcpStream) -> Result<()> {
+    stream.set_read_timeout(Some(HTTP_READ_TIMEOUT))?;
+    let request = read_request(&stream)?;
+    debug!("文字起こしサーバー: {} {}", request.method, request.path);
+
+    match (request.method.as_str(), request.path.as_str()) {
+        ("POST", "/v1/audio/transcriptions") => match transcribe(stt, &request) {
+            Ok((content_type, body)) => write_response(&mut stream, "200 OK", content_type, body.as_bytes()),
+            Err(e) => {
+                warn!("文字起こしに失敗: {:#}", e);
+                let body = json!({ "error": { "message": format!("{:#}", e) } }).to_string();
+                write_response(&mut stream, "400 Bad Request", "application/json", body.as_bytes())
+            }
+        },
+        ("GET", "/health") => write_response(&mut stream, "200 OK", "text/plain", b"ok\n"),
+        _ => write_response(&mut stream, "404 Not Found", "text/plain", b"not found\n"),
+    }
+}
+
+/// リクエストライン・ヘッダー・ボディ（Content-Length分）を読み取る
+fn read_request(stream: &TcpStream) -> Result<Request> {
+    let mut reader = BufReader::new(stream);
+
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or("").to_string();
+    let path = parts.next().unwrap_or("").to_string();
+
+    let mut content_length = 0;
+    let mut content_type = String::new();
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 || header == "\r\n" || header == "\n" {
+            break;
+        }
+        let Some((name, value)) = header.split_once(':') else {
+            continue;
+        };
+        match name.trim().to_ascii_lowercase().as_str() {
+            "content-length" => content_length = value.trim().parse().context("Content-Lengthが不正です")?,
+            "content-type" => content_type = value.trim().to_string(),
+            _ => {}
+        }
+    }
+
+    if content_length > MAX_BODY_BYTES {
+        anyhow::bail!("リクエストが大きすぎます: {} bytes", content_length);
+    }
+    let mut body = vec![0u8; content_length];
+    reader.read_exact(&mut body)?;
+
+    Ok(Request {
+        method,
+        path,
+        content_type,
+        body,
+    })
+}
+
+/// 文字起こしリクエストを処理し、(Content-Type, ボディ)を返す
+fn transcribe(stt: &WhisperStt, request: &Request) -> Result<(&'static str, String)> {
+    let boundary = request
+        .content_type
+        .split(';')
+        .find_map(|param| param.trim().strip_prefix("boundary="))
+        .map(|boundary| boundary.trim_matches('"'))
+        .context("multipart/form-dataのboundaryがありません")?;
+    let fields = parse_multipart(&request.body, boundary);
+
+    let file = fields.get("file").context("fileフィールドがありません")?;
+    let (samples, sample_rate) = wav::decode_wav_bytes(file)?;
+    let audio = resample(&samples, sample_rate, STT_SAMPLE_RATE);
+    let prompt = fields
+        .get("prompt")
+        .map(|prompt| String::from_utf8_lossy(prompt).into_owned())
+        .filter(|prompt| !prompt.is_empty());
+    let format = fields
+        .get("response_format")
+        .map(|format| String::from_utf8_lossy(format).into_owned())
+        .unwrap_or_else(|| "json".to_string());
+    // OpenAI互換APIと同じく、languageの省略は自動判定
+    let language = fields
+        .get("language")
+        .map(|language| String::from_utf8_lossy(language).trim().to_lowercase())
+        .filter(|language| !language.is_empty())
+        .unwrap_or_else(|| "auto".to_string());
+    if language != "auto" && whisper_rs::get_lang_id(&language).is_none() {
+        anyhow::bail!("languageが不正です: \"{}\"", language);
+    }
+    let temperature = match fields.get("temperature") {
+        Some(temperature) => {
+            let temperature = String::from_utf8_lossy(temperature);
+            let temperature: f32 = temperature
+                .trim()
+                .parse()
+                .with_context(|| format!("temperatureが不正です: \"{}\"", temperature))?;
+            if !(0.0..=1.0).contains(&temperature) {
+                anyhow::bail!("temperatureは0.0〜1.0で指定してください: {}", temperature);
+            }
+            Some(temperature)
+        }
+        None => None,
+    };
+
+    let start = Instant::now();
+    let transcript = stt.transcribe_request(&audio, prompt.as_deref(), &language, temperature)?;
+    info!(
+        "文字起こし: {:.2}秒 (language={}) -> \"{}\" ({:.0}ms)",
+        audio.len() as f32 / STT_SAMPLE_RATE as f32,
+        language,
+        transcript.text,
+        start.elapsed().as_secs_f32() * 1000.0
+    );
+
+    Ok(match format.as_str() {
+        "text" => ("text/plain", transcript.text),
+        "verbose_json" => ("application/json", verbose_json(&transcript, &audio).to_string()),
+        _ => ("application/json", json!({ "text": transcript.text }).to_string()),
+    })
+}
+
+/// OpenAI互換のverbose_json形式
+fn verbose_json(transcript: &Transcript, audio: &[f32]) -> serde_json::Value {
+    let segments: Vec<_> = transcript
+        .segments
+        .iter()
+        .enumerate()
+        .map(|(id, segment)| {
+            // トークン確率の対数の平均（トークンがなければ0）
+            let avg_logprob = if segment.tokens.is_empty() {
+                0.0
+            } else {
+                segment.tokens.iter().map(|t| t.probability.max(f32::MIN_POSITIVE).ln()).sum::<f32>()
+                    / segment.tokens.len() as f32
+            };
+            json!({
+                "id": id,
+                "start": segment.start,
+                "end": segment.end,
+                "text": segment.text,
+                "avg_logprob": avg_logprob,
+                "no_speech_prob": segment.no_speech_prob,
+            })
+        })
+        .collect();
+
+    json!({
+        "task": "transcribe",
+        "language": transcript.language,
+        "duration": audio.len() as f32 / STT_SAMPLE_RATE as f32,
+        "text": transcript.text,
+        "segments": segments,
+    })
+}
+
+/// multipart/form-dataをフィールド名ごとのデータに分解
+fn parse_multipart(body: &[u8], boundary: &str) -> HashMap<String, Vec<u8>> {
+    let delimiter = format!("--{}", boundary).into_bytes();
+    let mut fields = HashMap::new();
+
+    let mut rest = body;
+    while let Some(pos) = find(rest, &delimiter) {
+        rest = &rest[pos + delimiter.len()..];
+        // 終端（--boundary--）
+        if rest.starts_with(b"--") {
+            break;
+        }
+        let part_end = find(rest, &delimiter).unwrap_or(rest.len());
+        let part = rest[..part_end].strip_prefix(b"\r\n").unwrap_or(&rest[..part_end]);
+        let Some(header_end) = find(part, b"\r\n\r\n") else {
+            continue;
+        };
+
+        let headers = String::from_utf8_lossy(&part[..header_end]);
+        let name = headers
+            .split(';')
+            .find_map(|param| param.trim().strip_prefix("name=\"")?.split('"').next())
+            .map(str::to_string);
+        let data = &part[header_end + 4..];
+        let data = data.strip_suffix(b"\r\n").unwrap_or(data);
+        if let Some(name) = name {
+            fields.insert(name, data.to_vec());
+        }
+    }
+    fields
+}
+
+/// バイト列の中でneedleが最初に現れる位置
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|window| window == needle)
+}
+
+/// HTTPレスポンスを書き込む
+fn write_response(stream: &mut TcpStream, status: &str, content_type: &str, body: &[u8]) -> Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        status,
+        content_type,
+        body.len()
+    )?;
+    stream.write_all(body)?;
+    stream.flush()?;
+    Ok(())
+}
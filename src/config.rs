@@ -1,5 +1,7 @@
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
+use chrono::{NaiveTime, Timelike};
 use serde::Deserialize;
+use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
 
@@ -11,13 +13,35 @@ pub struct Config {
     pub stt: SttConfig,
     pub llm: LlmConfig,
     pub tts: TtsConfig,
+    /// 会話（フォローアップ）の設定
+    #[serde(default)]
+    pub conversation: ConversationConfig,
+    /// 話者識別の設定
+    #[serde(default)]
+    pub speaker: SpeakerConfig,
+    /// 聞き取り開始のトリガー設定
+    #[serde(default)]
+    pub trigger: TriggerConfig,
+    /// ウェイクワードごとのペルソナ（プロファイル名 → 設定）
+    #[serde(default)]
+    pub profiles: HashMap<String, ProfileConfig>,
 }
 
-/// ウェイクワード検出の設定（Rustpotter）
-#[derive(Debug, Deserialize)]
+/// ウェイクワード検出の設定
+#[derive(Debug, Clone, Deserialize)]
 pub struct WakewordConfig {
-    /// ウェイクワードファイルのパス（.rpwファイル）
-    pub wakeword_path: String,
+    /// 検出エンジン（"rustpotter", "whisper"、デフォルト"rustpotter"）
+    #[serde(default)]
+    pub engine: WakewordEngineKind,
+    /// Whisperフレーズスポッターの設定（engine = "whisper" の場合に使用）
+    #[serde(default)]
+    pub whisper_spotter: WhisperSpotterConfig,
+    /// ウェイクワードファイルのパス（.rpwファイル、keywords未指定時に使用）
+    #[serde(default)]
+    pub wakeword_path: Option<String>,
+    /// 複数ウェイクワードの定義（指定時はwakeword_pathより優先）
+    #[serde(default)]
+    pub keywords: Vec<WakewordEntry>,
     /// 検出閾値（0.0〜1.0、デフォルト0.35）
     #[serde(default = "default_threshold")]
     pub threshold: f32,
@@ -27,6 +51,409 @@ pub struct WakewordConfig {
     /// 連続検出回数（単発の誤検出を防ぐ、デフォルト3）
     #[serde(default = "default_min_scores")]
     pub min_scores: usize,
+    /// 独自の前処理（音量正規化 + 簡易VAD）を行う（デフォルトtrue）
+    ///
+    /// Rustpotterのゲイン正規化・VADを使う場合はfalseにする。
+    #[serde(default = "default_custom_preprocessing")]
+    pub custom_preprocessing: bool,
+    /// 閾値を超えた時点で即座に検出する（省略時はRustpotterのデフォルト）
+    #[serde(default)]
+    pub eager: Option<bool>,
+    /// スコア計算の基準値（大きいほどスコアが厳しくなる）
+    #[serde(default)]
+    pub score_ref: Option<f32>,
+    /// 特徴量比較のバンドサイズ
+    #[serde(default)]
+    pub band_size: Option<u16>,
+    /// 複数サンプルのスコア集約方法（"max", "avg", "median", "p25"〜"p95"）
+    #[serde(default)]
+    pub score_mode: Option<ScoreMode>,
+    /// RustpotterのVAD（"off", "easy", "medium", "hard"）
+    #[serde(default)]
+    pub vad_mode: Option<VadMode>,
+    /// Rustpotterの入力フィルタ
+    #[serde(default)]
+    pub filters: WakewordFiltersConfig,
+    /// 応答再生中の自己検出対策（"off", "strict", "ignore"、デフォルト"strict"）
+    #[serde(default)]
+    pub self_trigger_mode: SelfTriggerMode,
+    /// 再生終了後も自己検出対策を続ける時間（秒、デフォルト0.5）
+    #[serde(default = "default_self_trigger_holdoff")]
+    pub self_trigger_holdoff: f32,
+    /// strictモードで再生中に受理する最低スコア（デフォルト0.6）
+    #[serde(default = "default_self_trigger_threshold")]
+    pub self_trigger_threshold: f32,
+    /// Whisperによる二段階検証
+    #[serde(default)]
+    pub verification: VerificationConfig,
+    /// 再学習・閾値調整用のクリップ収集
+    #[serde(default)]
+    pub collection: CollectionConfig,
+    /// 時間帯ごとの閾値（該当する時間帯はthreshold等を上書き）
+    #[serde(default)]
+    pub schedules: Vec<SensitivitySchedule>,
+    /// 環境ノイズに応じた自動感度調整
+    #[serde(default)]
+    pub auto_sensitivity: AutoSensitivityConfig,
+}
+
+/// ウェイクワード検出エンジンの種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WakewordEngineKind {
+    /// Rustpotter（.rpwモデル）
+    #[default]
+    Rustpotter,
+    /// 小型Whisperモデルによるスライディングウィンドウのフレーズ検出
+    Whisper,
+}
+
+/// Whisperフレーズスポッターの設定
+#[derive(Debug, Clone, Deserialize)]
+pub struct WhisperSpotterConfig {
+    /// Whisperモデルファイルのパス（tiny/base程度の小型モデル推奨）
+    #[serde(default = "default_spotter_model_path")]
+    pub model_path: String,
+    /// 認識する区間の長さ（秒、デフォルト1.5）
+    #[serde(default = "default_spotter_window_seconds")]
+    pub window_seconds: f32,
+    /// 認識を行う間隔（秒、デフォルト0.5）
+    #[serde(default = "default_spotter_hop_seconds")]
+    pub hop_seconds: f32,
+    /// 検出とみなすフレーズとの最低類似度（0.0〜1.0、デフォルト0.8）
+    #[serde(default = "default_spotter_min_similarity")]
+    pub min_similarity: f32,
+    /// 直近の区間にこのRMS以上の音声がなければ認識を省略する（デフォルト0.01）
+    #[serde(default = "default_spotter_min_rms")]
+    pub min_rms: f32,
+}
+
+impl Default for WhisperSpotterConfig {
+    fn default() -> Self {
+        Self {
+            model_path: default_spotter_model_path(),
+            window_seconds: default_spotter_window_seconds(),
+            hop_seconds: default_spotter_hop_seconds(),
+            min_similarity: default_spotter_min_similarity(),
+            min_rms: default_spotter_min_rms(),
+        }
+    }
+}
+
+fn default_spotter_model_path() -> String {
+    "models/ggml-tiny.bin".to_string()
+}
+
+fn default_spotter_window_seconds() -> f32 {
+    1.5
+}
+
+fn default_spotter_hop_seconds() -> f32 {
+    0.5
+}
+
+fn default_spotter_min_similarity() -> f32 {
+    0.8
+}
+
+fn default_spotter_min_rms() -> f32 {
+    0.01
+}
+
+/// 応答再生中のウェイクワード検出の扱い
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SelfTriggerMode {
+    /// 通常どおり検出する
+    Off,
+    /// より高いスコアの検出のみ受理する
+    #[default]
+    Strict,
+    /// 検出を無視する
+    Ignore,
+}
+
+/// Rustpotterのスコア集約方法
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScoreMode {
+    Max,
+    Avg,
+    Median,
+    P25,
+    P50,
+    P75,
+    P80,
+    P90,
+    P95,
+}
+
+/// RustpotterのVADモード
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VadMode {
+    /// VADを使わない
+    Off,
+    Easy,
+    Medium,
+    Hard,
+}
+
+/// Rustpotterの入力フィルタ設定（省略した項目はRustpotterのデフォルト）
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct WakewordFiltersConfig {
+    /// ゲイン正規化フィルタを有効にする
+    #[serde(default)]
+    pub gain_normalizer: Option<bool>,
+    /// ゲイン正規化の基準RMS（省略時はウェイクワードから算出）
+    #[serde(default)]
+    pub gain_ref: Option<f32>,
+    /// ゲイン正規化の最小ゲイン
+    #[serde(default)]
+    pub min_gain: Option<f32>,
+    /// ゲイン正規化の最大ゲイン
+    #[serde(default)]
+    pub max_gain: Option<f32>,
+    /// バンドパスフィルタを有効にする
+    #[serde(default)]
+    pub band_pass: Option<bool>,
+    /// バンドパスの下限周波数（Hz）
+    #[serde(default)]
+    pub low_cutoff: Option<f32>,
+    /// バンドパスの上限周波数（Hz）
+    #[serde(default)]
+    pub high_cutoff: Option<f32>,
+}
+
+/// Whisperによるウェイクワード二段階検証の設定
+#[derive(Debug, Clone, Deserialize)]
+pub struct VerificationConfig {
+    /// 検証を有効にする（デフォルトfalse）
+    #[serde(default)]
+    pub enabled: bool,
+    /// 照合するフレーズ（キーワードごとのphrases未指定時に使用、空ならキーワード名）
+    #[serde(default)]
+    pub phrases: Vec<String>,
+    /// 受理する最低類似度（0.0〜1.0、デフォルト0.6）
+    #[serde(default = "default_verification_min_similarity")]
+    pub min_similarity: f32,
+    /// Whisperに与える初期プロンプト（省略時は照合フレーズから生成）
+    #[serde(default)]
+    pub prompt: Option<String>,
+}
+
+impl Default for VerificationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            phrases: Vec::new(),
+            min_similarity: default_verification_min_similarity(),
+            prompt: None,
+        }
+    }
+}
+
+fn default_verification_min_similarity() -> f32 {
+    0.6
+}
+
+/// 時間帯ごとの検出閾値（省略した項目は[wakeword]の値）
+#[derive(Debug, Clone, Deserialize)]
+pub struct SensitivitySchedule {
+    /// 開始時刻（"HH:MM"、ローカル時刻）
+    pub start: String,
+    /// 終了時刻（"HH:MM"、開始より前なら日付をまたぐ）
+    pub end: String,
+    #[serde(default)]
+    pub threshold: Option<f32>,
+    #[serde(default)]
+    pub avg_threshold: Option<f32>,
+    #[serde(default)]
+    pub min_scores: Option<usize>,
+}
+
+/// 環境ノイズに応じた自動感度調整の設定
+#[derive(Debug, Clone, Deserialize)]
+pub struct AutoSensitivityConfig {
+    /// 自動調整を有効にする（デフォルトfalse）
+    #[serde(default)]
+    pub enabled: bool,
+    /// このノイズフロア（RMS）以下では閾値を上げない（デフォルト0.005）
+    #[serde(default = "default_quiet_rms")]
+    pub quiet_rms: f32,
+    /// このノイズフロア（RMS）以上で閾値をmax_boostだけ上げる（デフォルト0.05）
+    #[serde(default = "default_noisy_rms")]
+    pub noisy_rms: f32,
+    /// 閾値の最大上乗せ量（デフォルト0.15）
+    #[serde(default = "default_max_boost")]
+    pub max_boost: f32,
+}
+
+impl Default for AutoSensitivityConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            quiet_rms: default_quiet_rms(),
+            noisy_rms: default_noisy_rms(),
+            max_boost: default_max_boost(),
+        }
+    }
+}
+
+fn default_quiet_rms() -> f32 {
+    0.005
+}
+
+fn default_noisy_rms() -> f32 {
+    0.05
+}
+
+fn default_max_boost() -> f32 {
+    0.15
+}
+
+/// ウェイクワードクリップ収集の設定
+#[derive(Debug, Clone, Deserialize)]
+pub struct CollectionConfig {
+    /// 収集を有効にする（デフォルトfalse）
+    #[serde(default)]
+    pub enabled: bool,
+    /// 保存先ディレクトリ（種類ごとのサブディレクトリに保存、デフォルト"dataset/wakeword"）
+    #[serde(default = "default_collection_dir")]
+    pub dir: String,
+    /// near_missとみなす部分スコア（thresholdに対する比率、デフォルト0.75）
+    #[serde(default = "default_near_miss_ratio")]
+    pub near_miss_ratio: f32,
+    /// near_missを保存した後、次を保存しない時間（秒、デフォルト5.0）
+    #[serde(default = "default_near_miss_cooldown")]
+    pub near_miss_cooldown: f32,
+}
+
+impl Default for CollectionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            dir: default_collection_dir(),
+            near_miss_ratio: default_near_miss_ratio(),
+            near_miss_cooldown: default_near_miss_cooldown(),
+        }
+    }
+}
+
+fn default_collection_dir() -> String {
+    "dataset/wakeword".to_string()
+}
+
+fn default_near_miss_ratio() -> f32 {
+    0.75
+}
+
+fn default_near_miss_cooldown() -> f32 {
+    5.0
+}
+
+/// ウェイクワード1件の定義
+#[derive(Debug, Clone, Deserialize)]
+pub struct WakewordEntry {
+    /// ウェイクワードファイルのパス（.rpwファイル、engine = "rustpotter" では必須）
+    #[serde(default)]
+    pub path: Option<String>,
+    /// 検出時のキーワード名（省略時はファイル名、pathがなければ必須）
+    #[serde(default)]
+    pub name: Option<String>,
+    /// 検出時に使用するプロファイル名（省略時は[llm]/[tts]の設定）
+    #[serde(default)]
+    pub profile: Option<String>,
+    /// 二段階検証で照合するフレーズ（例: ["さくら", "sakura"]）
+    #[serde(default)]
+    pub phrases: Vec<String>,
+}
+
+impl WakewordEntry {
+    /// キーワード名（nameが省略されていればファイル名から生成）
+    pub fn key(&self) -> String {
+        self.name.clone().unwrap_or_else(|| {
+            self.path
+                .as_deref()
+                .and_then(|path| Path::new(path).file_stem())
+                .and_then(|s| s.to_str())
+                .unwrap_or("wakeword")
+                .to_string()
+        })
+    }
+}
+
+impl WakewordConfig {
+    /// 有効なウェイクワード一覧（keywords未指定時はwakeword_pathの1件）
+    pub fn entries(&self) -> Vec<WakewordEntry> {
+        if !self.keywords.is_empty() {
+            return self.keywords.clone();
+        }
+        self.wakeword_path
+            .iter()
+            .map(|path| WakewordEntry {
+                path: Some(path.clone()),
+                name: None,
+                profile: None,
+                phrases: Vec::new(),
+            })
+            .collect()
+    }
+
+    /// 選択中のエンジンの検出閾値（rustpotter: threshold、whisper: min_similarity）
+    pub fn detection_threshold(&self) -> f32 {
+        match self.engine {
+            WakewordEngineKind::Rustpotter => self.threshold,
+            WakewordEngineKind::Whisper => self.whisper_spotter.min_similarity,
+        }
+    }
+
+    /// 設定値の範囲を検証
+    fn validate(&self) -> Result<()> {
+        let entries = self.entries();
+        if entries.is_empty() {
+            bail!("[wakeword] ウェイクワードが設定されていません（wakeword_path または keywords を指定してください）");
+        }
+        for entry in &entries {
+            match self.engine {
+                WakewordEngineKind::Rustpotter if entry.path.is_none() => bail!(
+                    "[[wakeword.keywords]] \"{}\" の path（.rpwファイル）を指定してください（engine = \"rustpotter\" では必須）",
+                    entry.key()
+                ),
+                WakewordEngineKind::Whisper if entry.path.is_none() && entry.name.is_none() => {
+                    bail!("[[wakeword.keywords]] name を指定してください（engine = \"whisper\" ではnameとphrasesで照合します）")
+                }
+                _ => {}
+            }
+        }
+
+        let mut thresholds = vec![
+            ("threshold", self.threshold),
+            ("avg_threshold", self.avg_threshold),
+            ("self_trigger_threshold", self.self_trigger_threshold),
+            ("whisper_spotter.min_similarity", self.whisper_spotter.min_similarity),
+            ("verification.min_similarity", self.verification.min_similarity),
+        ];
+        for schedule in &self.schedules {
+            parse_time_of_day(&schedule.start).context("[[wakeword.schedules]] start")?;
+            parse_time_of_day(&schedule.end).context("[[wakeword.schedules]] end")?;
+            thresholds.extend(schedule.threshold.map(|v| ("schedules.threshold", v)));
+            thresholds.extend(schedule.avg_threshold.map(|v| ("schedules.avg_threshold", v)));
+        }
+        for (name, value) in thresholds {
+            if !(0.0..=1.0).contains(&value) {
+                bail!("[wakeword] {} は0.0〜1.0で指定してください（指定値: {}）", name, value);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// "HH:MM"形式の時刻を0時からの秒数に変換
+pub fn parse_time_of_day(text: &str) -> Result<u32> {
+    let time = NaiveTime::parse_from_str(text, "%H:%M")
+        .map_err(|e| anyhow::anyhow!("時刻の形式が不正です（HH:MM）: \"{}\" - {}", text, e))?;
+    Ok(time.num_seconds_from_midnight())
 }
 
 fn default_threshold() -> f32 {
@@ -41,6 +468,18 @@ fn default_min_scores() -> usize {
     3
 }
 
+fn default_custom_preprocessing() -> bool {
+    true
+}
+
+fn default_self_trigger_holdoff() -> f32 {
+    0.5
+}
+
+fn default_self_trigger_threshold() -> f32 {
+    0.6
+}
+
 /// オーディオ入出力の設定
 #[derive(Debug, Deserialize)]
 pub struct AudioConfig {
@@ -70,6 +509,57 @@ pub struct AudioConfig {
     /// 連続した無音フレームがこの回数以上続いたら無音としてカウント
     #[serde(default = "default_debounce_frames")]
     pub debounce_frames: usize,
+    /// 入力バックエンド（"device" または "file"、デフォルト"device"）
+    #[serde(default)]
+    pub input_backend: InputBackend,
+    /// 入力WAVファイルのパス（input_backend = "file" のとき必須）
+    #[serde(default)]
+    pub input_file: Option<String>,
+    /// 出力バックエンド（"device", "file", "null"、デフォルト"device"）
+    #[serde(default)]
+    pub output_backend: OutputBackend,
+    /// WAV出力先（append: ファイルパス、rotate: ディレクトリ）
+    /// 未指定なら append: "output.wav"、rotate: "output"
+    #[serde(default)]
+    pub output_path: Option<String>,
+    /// WAV出力の方式（"append" または "rotate"、デフォルト"rotate"）
+    #[serde(default)]
+    pub output_file_mode: FileOutputMode,
+}
+
+/// 音声入力のバックエンド
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InputBackend {
+    /// デフォルトの入力デバイス（マイク）
+    #[default]
+    Device,
+    /// WAVファイルを実時間で再生して入力とする
+    File,
+}
+
+/// 音声出力のバックエンド
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputBackend {
+    /// デフォルトの出力デバイス（スピーカー）
+    #[default]
+    Device,
+    /// 応答ごとにWAVファイルへ書き出す
+    File,
+    /// 再生せず、再生時間だけ待機する
+    Null,
+}
+
+/// WAVファイル出力の方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FileOutputMode {
+    /// 1つのWAVファイルに追記
+    Append,
+    /// 応答ごとに連番ファイルを作成
+    #[default]
+    Rotate,
 }
 
 fn default_input_gain() -> f32 {
@@ -92,13 +582,435 @@ fn default_debounce_frames() -> usize {
     3
 }
 
+impl AudioConfig {
+    /// WAV出力先（output_path未指定時は出力方式に応じたデフォルト）
+    pub fn output_path(&self) -> &str {
+        match (&self.output_path, self.output_file_mode) {
+            (Some(path), _) => path,
+            (None, FileOutputMode::Append) => "output.wav",
+            (None, FileOutputMode::Rotate) => "output",
+        }
+    }
+}
+
 /// 音声認識（STT）の設定
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct SttConfig {
+    /// 音声認識のバックエンド（"whisper", "http"、デフォルト"whisper"）
+    #[serde(default)]
+    pub backend: SttBackendKind,
     /// Whisperモデルファイルのパス
     pub model_path: String,
     /// 認識言語（例: "ja", "en"）
     pub language: String,
+    /// デコード方式（"greedy", "beam"、デフォルト"beam"）
+    #[serde(default)]
+    pub strategy: DecodingStrategy,
+    /// ビームサーチのビーム幅（1〜8、デフォルト5）
+    #[serde(default = "default_beam_size")]
+    pub beam_size: i32,
+    /// ビームサーチのpatience（デフォルト1.0）
+    #[serde(default = "default_patience")]
+    pub patience: f32,
+    /// greedyでの候補数（温度 > 0 のとき、1〜8、デフォルト5）
+    #[serde(default = "default_best_of")]
+    pub best_of: i32,
+    /// 初期温度（0.0〜1.0、デフォルト0.0）
+    #[serde(default)]
+    pub temperature: f32,
+    /// デコード失敗時に温度を上げて再試行する幅（0.0で再試行なし、デフォルト0.2）
+    #[serde(default = "default_temperature_inc")]
+    pub temperature_inc: f32,
+    /// 再試行とみなすエントロピー閾値（繰り返し検出、デフォルト2.4）
+    #[serde(default = "default_entropy_thold")]
+    pub entropy_thold: f32,
+    /// 再試行とみなす平均対数確率の閾値（デフォルト-1.0）
+    #[serde(default = "default_logprob_thold")]
+    pub logprob_thold: f32,
+    /// この無音確率を超えるセグメントを捨てる（0.0〜1.0、デフォルト0.6）
+    #[serde(default = "default_no_speech_thold")]
+    pub no_speech_thold: f32,
+    /// 推論スレッド数（省略時はwhisper.cppのデフォルト）
+    #[serde(default)]
+    pub n_threads: Option<i32>,
+    /// GPUを使用する（GPU対応なしのビルド・GPUが見つからない場合はCPU、デフォルトtrue）
+    #[serde(default = "default_use_gpu")]
+    pub use_gpu: bool,
+    /// 使用するGPUの番号（デフォルト0）
+    #[serde(default)]
+    pub gpu_device: i32,
+    /// Flash Attentionを使用する（デフォルトfalse）
+    #[serde(default)]
+    pub flash_attn: bool,
+    /// 1セグメントとして出力する（短いコマンド向け、デフォルトfalse）
+    #[serde(default)]
+    pub single_segment: bool,
+    /// 起動時にダミー音声で推論してモデルを温める（デフォルトtrue）
+    #[serde(default = "default_warmup")]
+    pub warmup: bool,
+    /// Whisperに与える初期プロンプト（文体・語彙の誘導用）
+    #[serde(default)]
+    pub initial_prompt: Option<String>,
+    /// 固有名詞・デバイス名などのフレーズファイル（1行1フレーズ、`表記|よみ`で読みを指定）
+    #[serde(default)]
+    pub phrases_path: Option<String>,
+    /// 認識結果をフレーズに置き換える最低類似度（0.0〜1.0、デフォルト0.8）
+    #[serde(default = "default_phrase_min_similarity")]
+    pub phrase_min_similarity: f32,
+    /// 録音中の途中認識（ストリーミング）
+    #[serde(default)]
+    pub streaming: StreamingConfig,
+    /// ハルシネーション（無音・雑音からの誤った認識結果）の除去
+    #[serde(default)]
+    pub hallucination: HallucinationConfig,
+    /// HTTPバックエンド（OpenAI互換の文字起こしサーバー）
+    #[serde(default)]
+    pub http: HttpSttConfig,
+    /// 認識結果の日本語正規化
+    #[serde(default)]
+    pub normalize: NormalizeConfig,
+}
+
+/// 音声認識のバックエンド
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SttBackendKind {
+    /// whisper-rs（プロセス内でモデルを読み込む）
+    #[default]
+    Whisper,
+    /// OpenAI互換の文字起こしサーバー（POST /v1/audio/transcriptions）
+    Http,
+}
+
+/// HTTPバックエンドの設定
+#[derive(Debug, Clone, Deserialize)]
+pub struct HttpSttConfig {
+    /// 文字起こしサーバーのURL（`/v1/audio/transcriptions`より前の部分）
+    #[serde(default = "default_http_stt_endpoint")]
+    pub endpoint: String,
+    /// モデル名（デフォルト"whisper-1"）
+    #[serde(default = "default_http_stt_model")]
+    pub model: String,
+    /// APIキー（不要なサーバーでは省略）
+    #[serde(default)]
+    pub api_key: Option<String>,
+    /// リクエストのタイムアウト（秒、デフォルト30.0）
+    #[serde(default = "default_http_stt_timeout_seconds")]
+    pub timeout_seconds: f32,
+}
+
+impl Default for HttpSttConfig {
+    fn default() -> Self {
+        Self {
+            endpoint: default_http_stt_endpoint(),
+            model: default_http_stt_model(),
+            api_key: None,
+            timeout_seconds: default_http_stt_timeout_seconds(),
+        }
+    }
+}
+
+fn default_http_stt_endpoint() -> String {
+    "http://127.0.0.1:8178".to_string()
+}
+
+fn default_http_stt_model() -> String {
+    "whisper-1".to_string()
+}
+
+fn default_http_stt_timeout_seconds() -> f32 {
+    30.0
+}
+
+/// ハルシネーションフィルタの設定
+#[derive(Debug, Clone, Deserialize)]
+pub struct HallucinationConfig {
+    /// フィルタを有効にする（デフォルトtrue）
+    #[serde(default = "default_hallucination_enabled")]
+    pub enabled: bool,
+    /// 既知のハルシネーションフレーズ（認識結果がこれらだけの場合に棄却）
+    #[serde(default = "default_hallucination_blocklist")]
+    pub blocklist: Vec<String>,
+    /// 同じ語句がこの回数以上連続したら棄却（0で無効、デフォルト4）
+    #[serde(default = "default_max_repeats")]
+    pub max_repeats: usize,
+    /// トークンの平均確率がこれ未満なら棄却（0.0〜1.0、デフォルト0.3）
+    #[serde(default = "default_min_avg_token_prob")]
+    pub min_avg_token_prob: f32,
+    /// 音声1秒あたりの文字数がこれを超えたら棄却（デフォルト20.0）
+    #[serde(default = "default_max_chars_per_second")]
+    pub max_chars_per_second: f32,
+}
+
+impl Default for HallucinationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_hallucination_enabled(),
+            blocklist: default_hallucination_blocklist(),
+            max_repeats: default_max_repeats(),
+            min_avg_token_prob: default_min_avg_token_prob(),
+            max_chars_per_second: default_max_chars_per_second(),
+        }
+    }
+}
+
+fn default_hallucination_enabled() -> bool {
+    true
+}
+
+fn default_hallucination_blocklist() -> Vec<String> {
+    [
+        "ご視聴ありがとうございました",
+        "最後までご視聴いただきありがとうございました",
+        "チャンネル登録よろしくお願いします",
+        "チャンネル登録お願いします",
+        "字幕視聴ありがとうございました",
+        "ご清聴ありがとうございました",
+    ]
+    .iter()
+    .map(|s| s.to_string())
+    .collect()
+}
+
+fn default_max_repeats() -> usize {
+    4
+}
+
+fn default_min_avg_token_prob() -> f32 {
+    0.3
+}
+
+fn default_max_chars_per_second() -> f32 {
+    20.0
+}
+
+/// 認識結果の日本語正規化の設定
+///
+/// 認識言語が日本語（または不明）の場合のみ適用する。
+#[derive(Debug, Clone, Deserialize)]
+pub struct NormalizeConfig {
+    /// 正規化を有効にする（デフォルトtrue）
+    #[serde(default = "default_normalize_enabled")]
+    pub enabled: bool,
+    /// 全角英数字を半角に、半角カタカナを全角に揃える（デフォルトtrue）
+    #[serde(default = "default_normalize_width")]
+    pub width: bool,
+    /// 数字の表記（"digits", "kanji", "keep"、デフォルト"digits"）
+    #[serde(default)]
+    pub numerals: NumeralStyle,
+    /// 句読点・空白を整える（デフォルトtrue）
+    #[serde(default = "default_normalize_punctuation")]
+    pub punctuation: bool,
+    /// 取り除くフィラー（空で無効）
+    #[serde(default = "default_fillers")]
+    pub fillers: Vec<String>,
+}
+
+impl Default for NormalizeConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_normalize_enabled(),
+            width: default_normalize_width(),
+            numerals: NumeralStyle::default(),
+            punctuation: default_normalize_punctuation(),
+            fillers: default_fillers(),
+        }
+    }
+}
+
+/// 正規化後の数字の表記
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NumeralStyle {
+    /// 助数詞の前の漢数字を算用数字に（「三時十五分」→「3時15分」）
+    #[default]
+    Digits,
+    /// 算用数字を漢数字に（「3時15分」→「三時十五分」）
+    Kanji,
+    /// 変換しない
+    Keep,
+}
+
+fn default_normalize_enabled() -> bool {
+    true
+}
+
+fn default_normalize_width() -> bool {
+    true
+}
+
+fn default_normalize_punctuation() -> bool {
+    true
+}
+
+fn default_fillers() -> Vec<String> {
+    ["えーっと", "えーと", "ええと", "えっと", "えー", "あのー", "あの", "うーん", "んー", "そのー"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect()
+}
+
+/// Whisperのデコード方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DecodingStrategy {
+    /// 貪欲法（高速）
+    Greedy,
+    /// ビームサーチ（高精度）
+    #[default]
+    Beam,
+}
+
+/// whisper.cppが扱える最大のデコーダ数（beam_size / best_of の上限）
+const MAX_DECODERS: i32 = 8;
+
+fn default_beam_size() -> i32 {
+    5
+}
+
+fn default_patience() -> f32 {
+    1.0
+}
+
+fn default_best_of() -> i32 {
+    5
+}
+
+fn default_temperature_inc() -> f32 {
+    0.2
+}
+
+fn default_entropy_thold() -> f32 {
+    2.4
+}
+
+fn default_logprob_thold() -> f32 {
+    -1.0
+}
+
+fn default_no_speech_thold() -> f32 {
+    0.6
+}
+
+fn default_use_gpu() -> bool {
+    true
+}
+
+fn default_warmup() -> bool {
+    true
+}
+
+fn default_phrase_min_similarity() -> f32 {
+    0.8
+}
+
+impl SttConfig {
+    /// 設定値の範囲を検証
+    fn validate(&self) -> Result<()> {
+        if self.language.trim().is_empty() {
+            bail!("[stt] language が空です（\"ja\", \"en\", \"auto\" など）");
+        }
+        if self.language != "auto" && whisper_rs::get_lang_id(&self.language).is_none() {
+            bail!(
+                "[stt] language はWhisperの言語コードか\"auto\"で指定してください（指定値: \"{}\"）",
+                self.language
+            );
+        }
+        if !(1..=MAX_DECODERS).contains(&self.beam_size) {
+            bail!("[stt] beam_size は1〜{}で指定してください（指定値: {}）", MAX_DECODERS, self.beam_size);
+        }
+        if !(1..=MAX_DECODERS).contains(&self.best_of) {
+            bail!("[stt] best_of は1〜{}で指定してください（指定値: {}）", MAX_DECODERS, self.best_of);
+        }
+        if self.patience <= 0.0 {
+            bail!("[stt] patience は0より大きい値を指定してください（指定値: {}）", self.patience);
+        }
+        if !(0.0..=1.0).contains(&self.temperature) {
+            bail!("[stt] temperature は0.0〜1.0で指定してください（指定値: {}）", self.temperature);
+        }
+        if !(0.0..=1.0).contains(&self.temperature_inc) {
+            bail!("[stt] temperature_inc は0.0〜1.0で指定してください（指定値: {}）", self.temperature_inc);
+        }
+        if self.entropy_thold <= 0.0 {
+            bail!("[stt] entropy_thold は0より大きい値を指定してください（指定値: {}）", self.entropy_thold);
+        }
+        if self.logprob_thold > 0.0 {
+            bail!("[stt] logprob_thold は0以下で指定してください（指定値: {}）", self.logprob_thold);
+        }
+        if !(0.0..=1.0).contains(&self.no_speech_thold) {
+            bail!("[stt] no_speech_thold は0.0〜1.0で指定してください（指定値: {}）", self.no_speech_thold);
+        }
+        if !(0.0..=1.0).contains(&self.phrase_min_similarity) {
+            bail!(
+                "[stt] phrase_min_similarity は0.0〜1.0で指定してください（指定値: {}）",
+                self.phrase_min_similarity
+            );
+        }
+        if !(0.0..=1.0).contains(&self.hallucination.min_avg_token_prob) {
+            bail!(
+                "[stt.hallucination] min_avg_token_prob は0.0〜1.0で指定してください（指定値: {}）",
+                self.hallucination.min_avg_token_prob
+            );
+        }
+        if self.hallucination.max_chars_per_second <= 0.0 {
+            bail!(
+                "[stt.hallucination] max_chars_per_second は0より大きい値を指定してください（指定値: {}）",
+                self.hallucination.max_chars_per_second
+            );
+        }
+        if self.gpu_device < 0 {
+            bail!("[stt] gpu_device は0以上で指定してください（指定値: {}）", self.gpu_device);
+        }
+        if let Some(n_threads) = self.n_threads.filter(|&n| n < 1) {
+            bail!("[stt] n_threads は1以上で指定してください（指定値: {}）", n_threads);
+        }
+        if self.streaming.interval_seconds <= 0.0 || self.streaming.window_seconds <= 0.0 {
+            bail!(
+                "[stt.streaming] interval_seconds と window_seconds は0より大きい値を指定してください（指定値: {}, {}）",
+                self.streaming.interval_seconds,
+                self.streaming.window_seconds
+            );
+        }
+        if self.http.timeout_seconds <= 0.0 {
+            bail!(
+                "[stt.http] timeout_seconds は0より大きい値を指定してください（指定値: {}）",
+                self.http.timeout_seconds
+            );
+        }
+        Ok(())
+    }
+}
+
+/// ストリーミング認識の設定
+#[derive(Debug, Clone, Deserialize)]
+pub struct StreamingConfig {
+    /// 録音中に途中認識を行う（デフォルトfalse）
+    #[serde(default)]
+    pub enabled: bool,
+    /// 途中認識の間隔（秒、デフォルト0.5）
+    #[serde(default = "default_streaming_interval_seconds")]
+    pub interval_seconds: f32,
+    /// 途中認識する最大区間（直近の秒数、デフォルト10.0）
+    #[serde(default = "default_streaming_window_seconds")]
+    pub window_seconds: f32,
+}
+
+impl Default for StreamingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_seconds: default_streaming_interval_seconds(),
+            window_seconds: default_streaming_window_seconds(),
+        }
+    }
+}
+
+fn default_streaming_interval_seconds() -> f32 {
+    0.5
+}
+
+fn default_streaming_window_seconds() -> f32 {
+    10.0
 }
 
 /// LLM（大規模言語モデル）の設定
@@ -110,6 +1022,11 @@ pub struct LlmConfig {
     pub model: String,
     /// システムプロンプト
     pub system_prompt: String,
+    /// 認識言語ごとのシステムプロンプト（キーはWhisperの言語コード、未指定の言語はsystem_prompt）
+    ///
+    /// 言語で切り替わるのは[stt] language = "auto"の場合のみ。
+    #[serde(default)]
+    pub system_prompts: HashMap<String, String>,
 }
 
 /// 音声合成（TTS）の設定
@@ -121,6 +1038,234 @@ pub struct TtsConfig {
     pub speaker_id: i32,
     /// 話速（0.5〜2.0）
     pub speed: f32,
+    /// 認識言語ごとの音声（キーはWhisperの言語コード、未指定の言語はVOICEVOX）
+    ///
+    /// 言語で切り替わるのは[stt] language = "auto"の場合のみ。
+    #[serde(default)]
+    pub voices: HashMap<String, VoiceConfig>,
+}
+
+/// 言語ごとの音声合成エンジンの設定
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "engine", rename_all = "snake_case")]
+pub enum VoiceConfig {
+    /// VOICEVOX（[tts] のエンドポイントを使用）
+    Voicevox {
+        /// 話者ID（省略時はプロファイルの話者）
+        #[serde(default)]
+        speaker_id: Option<i32>,
+    },
+    /// OpenAI互換の音声合成API（POST /v1/audio/speech、WAVで受け取る）
+    Openai {
+        /// エンドポイントURL（例: "http://localhost:8880"）
+        endpoint: String,
+        /// モデル名（デフォルト"tts-1"）
+        #[serde(default = "default_openai_tts_model")]
+        model: String,
+        /// 音声名
+        voice: String,
+        /// APIキー（不要なサーバーでは省略）
+        #[serde(default)]
+        api_key: Option<String>,
+    },
+}
+
+fn default_openai_tts_model() -> String {
+    "tts-1".to_string()
+}
+
+/// 会話（フォローアップ）の設定
+#[derive(Debug, Deserialize)]
+pub struct ConversationConfig {
+    /// 応答後、ウェイクワードなしで次の発話を待つ秒数（0で無効、デフォルト0）
+    #[serde(default)]
+    pub follow_up_seconds: f32,
+    /// フォローアップ待機の開始時にチャイムを鳴らす（デフォルトtrue）
+    #[serde(default = "default_follow_up_chime")]
+    pub follow_up_chime: bool,
+    /// チャイムの音量（0.0〜1.0、デフォルト0.2）
+    #[serde(default = "default_chime_volume")]
+    pub chime_volume: f32,
+    /// LLMに渡す会話履歴の最大往復数（デフォルト10）
+    #[serde(default = "default_max_history_turns")]
+    pub max_history_turns: usize,
+    /// 応答中のウェイクワードで応答を中断して次の発話を聞く（デフォルトtrue）
+    #[serde(default = "default_barge_in")]
+    pub barge_in: bool,
+}
+
+impl Default for ConversationConfig {
+    fn default() -> Self {
+        Self {
+            follow_up_seconds: 0.0,
+            follow_up_chime: default_follow_up_chime(),
+            chime_volume: default_chime_volume(),
+            max_history_turns: default_max_history_turns(),
+            barge_in: default_barge_in(),
+        }
+    }
+}
+
+fn default_follow_up_chime() -> bool {
+    true
+}
+
+fn default_chime_volume() -> f32 {
+    0.2
+}
+
+fn default_max_history_turns() -> usize {
+    10
+}
+
+fn default_barge_in() -> bool {
+    true
+}
+
+/// 聞き取り開始のトリガー設定
+#[derive(Debug, Deserialize)]
+pub struct TriggerConfig {
+    /// 有効な入力元（"wakeword", "keyboard", "http"、デフォルト["wakeword"]）
+    #[serde(default = "default_trigger_sources")]
+    pub sources: Vec<TriggerSourceKind>,
+    /// キーボード・HTTPトリガーのプッシュトゥトーク（"off", "hold", "toggle"、デフォルト"off"）
+    #[serde(default)]
+    pub push_to_talk: PushToTalkMode,
+    /// プッシュトゥトークの最大録音時間（秒、デフォルト30）
+    ///
+    /// 停止の操作（離す・再度の押下）が届かなくても、この時間で録音を終える。
+    #[serde(default = "default_push_to_talk_max_seconds")]
+    pub push_to_talk_max_seconds: f32,
+    /// HTTPトリガーの待ち受けアドレス（デフォルト"127.0.0.1:8765"）
+    #[serde(default = "default_trigger_http_addr")]
+    pub http_addr: String,
+}
+
+impl Default for TriggerConfig {
+    fn default() -> Self {
+        Self {
+            sources: default_trigger_sources(),
+            push_to_talk: PushToTalkMode::default(),
+            push_to_talk_max_seconds: default_push_to_talk_max_seconds(),
+            http_addr: default_trigger_http_addr(),
+        }
+    }
+}
+
+impl TriggerConfig {
+    /// ウェイクワードによる聞き取り開始が有効か
+    pub fn wakeword_enabled(&self) -> bool {
+        self.sources.contains(&TriggerSourceKind::Wakeword)
+    }
+
+    /// 設定値の範囲を検証
+    fn validate(&self) -> Result<()> {
+        if !(self.push_to_talk_max_seconds > 0.0 && self.push_to_talk_max_seconds.is_finite()) {
+            bail!(
+                "[trigger] push_to_talk_max_seconds は0より大きい値を指定してください（指定値: {}）",
+                self.push_to_talk_max_seconds
+            );
+        }
+        Ok(())
+    }
+}
+
+/// 聞き取り開始の入力元
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TriggerSourceKind {
+    /// ウェイクワード
+    Wakeword,
+    /// 標準入力のEnter
+    Keyboard,
+    /// ローカルHTTPリクエスト
+    Http,
+}
+
+/// キーボード・HTTPトリガーでの録音の終わらせ方
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PushToTalkMode {
+    /// 通常どおり無音で録音を終了する
+    #[default]
+    Off,
+    /// 押している間だけ録音する（HTTPの/ptt/down〜/ptt/upのみ）
+    ///
+    /// キーボードは離したことを検出できないため、toggleと同じく再度のEnterで停止する。
+    Hold,
+    /// 押すたびに録音の開始/停止を切り替える
+    Toggle,
+}
+
+fn default_trigger_sources() -> Vec<TriggerSourceKind> {
+    vec![TriggerSourceKind::Wakeword]
+}
+
+fn default_push_to_talk_max_seconds() -> f32 {
+    30.0
+}
+
+fn default_trigger_http_addr() -> String {
+    "127.0.0.1:8765".to_string()
+}
+
+/// 話者識別の設定
+#[derive(Debug, Deserialize)]
+pub struct SpeakerConfig {
+    /// 話者識別を有効にする（デフォルトfalse）
+    #[serde(default)]
+    pub enabled: bool,
+    /// 話者プロファイルの保存先（JSON、デフォルト"speakers.json"）
+    #[serde(default = "default_speaker_profiles_path")]
+    pub profiles_path: String,
+    /// 話者埋め込みの抽出器（デフォルト"mfcc"）
+    #[serde(default = "default_speaker_extractor")]
+    pub extractor: String,
+    /// 話者と判定する最低信頼度（0.0〜1.0、デフォルト0.85）
+    #[serde(default = "default_speaker_min_confidence")]
+    pub min_confidence: f32,
+}
+
+impl Default for SpeakerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            profiles_path: default_speaker_profiles_path(),
+            extractor: default_speaker_extractor(),
+            min_confidence: default_speaker_min_confidence(),
+        }
+    }
+}
+
+fn default_speaker_profiles_path() -> String {
+    "speakers.json".to_string()
+}
+
+fn default_speaker_extractor() -> String {
+    "mfcc".to_string()
+}
+
+fn default_speaker_min_confidence() -> f32 {
+    0.85
+}
+
+/// ペルソナ（プロファイル）の設定
+///
+/// 省略した項目は[llm]/[tts]の値を使用する。
+#[derive(Debug, Clone, Deserialize)]
+pub struct ProfileConfig {
+    /// システムプロンプト
+    #[serde(default)]
+    pub system_prompt: Option<String>,
+    /// 使用するOllamaモデル名
+    #[serde(default)]
+    pub model: Option<String>,
+    /// VOICEVOXの話者ID
+    #[serde(default)]
+    pub speaker_id: Option<i32>,
+    /// 認識言語ごとのシステムプロンプト
+    #[serde(default)]
+    pub system_prompts: HashMap<String, String>,
 }
 
 impl Config {
@@ -132,7 +1277,17 @@ impl Config {
 
         let config: Config = toml::from_str(&content)
             .with_context(|| format!("設定ファイルのパースに失敗: {}", path.display()))?;
+        config
+            .validate()
+            .with_context(|| format!("設定値が不正です: {}", path.display()))?;
 
         Ok(config)
     }
+
+    /// 設定値を検証（読み込み時に呼ばれる）
+    fn validate(&self) -> Result<()> {
+        self.wakeword.validate()?;
+        self.stt.validate()?;
+        self.trigger.validate()
+    }
 }
@@ -0,0 +1,46 @@
+use log::debug;
+
+use super::ollama::ChatMessage;
+
+/// フォローアップ中に引き継ぐ会話履歴
+///
+/// ウェイクワードで会話が始まるたびにクリアし、
+/// 古い往復から順に捨てて最大`max_turns`往復を保持する。
+pub struct Conversation {
+    messages: Vec<ChatMessage>,
+    max_turns: usize,
+}
+
+impl Conversation {
+    pub fn new(max_turns: usize) -> Self {
+        Self {
+            messages: Vec::new(),
+            max_turns,
+        }
+    }
+
+    /// LLMに渡す履歴
+    pub fn history(&self) -> &[ChatMessage] {
+        &self.messages
+    }
+
+    /// 1往復（ユーザー発話と応答）を追加
+    pub fn push_turn(&mut self, user: &str, assistant: &str) {
+        self.messages.push(ChatMessage::user(user));
+        self.messages.push(ChatMessage::assistant(assistant));
+
+        let max_messages = self.max_turns * 2;
+        if self.messages.len() > max_messages {
+            let excess = self.messages.len() - max_messages;
+            self.messages.drain(..excess);
+        }
+    }
+
+    /// 会話履歴をクリア
+    pub fn clear(&mut self) {
+        if !self.messages.is_empty() {
+            debug!("会話履歴をクリア: {} 往復", self.messages.len() / 2);
+        }
+        self.messages.clear();
+    }
+}
@@ -1,3 +1,5 @@
+mod conversation;
 mod ollama;
 
+pub use conversation::Conversation;
 pub use ollama::OllamaLlm;
@@ -2,8 +2,10 @@ use anyhow::Result;
 use log::{debug, info};
 use reqwest::blocking::Client;
 use serde::{Deserialize, Serialize};
+use std::io::{BufRead, BufReader};
 use thiserror::Error;
 
+use crate::cancel::CancelToken;
 use crate::config::LlmConfig;
 
 /// LLM処理に関するエラー
@@ -16,27 +18,60 @@ pub enum LlmError {
     GenerationError(String),
 }
 
-/// Ollama API リクエスト
+/// 会話の1メッセージ（Ollama chat API）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatMessage {
+    /// "system", "user", "assistant"
+    pub role: String,
+    pub content: String,
+}
+
+impl ChatMessage {
+    pub fn user(content: &str) -> Self {
+        Self {
+            role: "user".to_string(),
+            content: content.to_string(),
+        }
+    }
+
+    pub fn assistant(content: &str) -> Self {
+        Self {
+            role: "assistant".to_string(),
+            content: content.to_string(),
+        }
+    }
+
+    fn system(content: &str) -> Self {
+        Self {
+            role: "system".to_string(),
+            content: content.to_string(),
+        }
+    }
+}
+
+/// Ollama chat API リクエスト
 #[derive(Debug, Serialize)]
-struct GenerateRequest {
+struct ChatRequest {
     model: String,
-    prompt: String,
-    system: String,
+    messages: Vec<ChatMessage>,
     stream: bool,
 }
 
-/// Ollama API レスポンス
+/// Ollama chat API レスポンス（ストリーミングの1チャンク）
 #[derive(Debug, Deserialize)]
-struct GenerateResponse {
-    response: String,
+struct ChatChunk {
+    #[serde(default)]
+    message: Option<ChatMessage>,
+    #[serde(default)]
+    done: bool,
+    #[serde(default)]
+    error: Option<String>,
 }
 
 /// Ollamaを使用したLLMエンジン
 pub struct OllamaLlm {
     client: Client,
     endpoint: String,
-    model: String,
-    system_prompt: String,
 }
 
 impl OllamaLlm {
@@ -58,28 +93,49 @@ impl OllamaLlm {
         Ok(Self {
             client,
             endpoint: config.endpoint.clone(),
-            model: config.model.clone(),
-            system_prompt: config.system_prompt.clone(),
         })
     }
 
-    /// プロンプトに対する応答を生成
+    /// 会話履歴を含めて応答を生成（chat API）
+    ///
+    /// 応答はストリーミングで受け取り、チャンクごとにキャンセルを確認する。
+    /// キャンセルされたら接続を閉じてOllama側の生成も打ち切る。
     ///
     /// # Arguments
+    /// * `history` - これまでの会話（user/assistantの交互）
     /// * `prompt` - ユーザーからの入力テキスト
+    /// * `model` - 使用するモデル名
+    /// * `system_prompt` - システムプロンプト
+    /// * `cancel` - 割り込みで生成を打ち切る
     ///
     /// # Returns
-    /// LLMからの応答テキスト
-    pub fn generate(&self, prompt: &str) -> Result<String> {
-        debug!("LLM応答生成開始: \"{}\"", prompt);
+    /// LLMからの応答テキスト（キャンセルされた場合はNone）
+    pub fn chat_with(
+        &self,
+        history: &[ChatMessage],
+        prompt: &str,
+        model: &str,
+        system_prompt: &str,
+        cancel: &CancelToken,
+    ) -> Result<Option<String>> {
+        debug!(
+            "LLM応答生成開始 (model={}, history={}): \"{}\"",
+            model,
+            history.len(),
+            prompt
+        );
 
-        let url = format!("{}/api/generate", self.endpoint);
+        let url = format!("{}/api/chat", self.endpoint);
 
-        let request = GenerateRequest {
-            model: self.model.clone(),
-            prompt: prompt.to_string(),
-            system: self.system_prompt.clone(),
-            stream: false,
+        let mut messages = Vec::with_capacity(history.len() + 2);
+        messages.push(ChatMessage::system(system_prompt));
+        messages.extend_from_slice(history);
+        messages.push(ChatMessage::user(prompt));
+
+        let request = ChatRequest {
+            model: model.to_string(),
+            messages,
+            stream: true,
         };
 
         let response = self
@@ -97,14 +153,37 @@ impl OllamaLlm {
             .into());
         }
 
-        let result: GenerateResponse = response
-            .json()
-            .map_err(|e| LlmError::GenerationError(e.to_string()))?;
+        // 1行に1チャンクのJSONが届く
+        let mut response_text = String::new();
+        for line in BufReader::new(response).lines() {
+            if cancel.is_cancelled() {
+                debug!("LLM応答生成を中断: \"{}\"", response_text);
+                return Ok(None);
+            }
+            let line = line.map_err(|e| LlmError::GenerationError(e.to_string()))?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let chunk: ChatChunk =
+                serde_json::from_str(&line).map_err(|e| LlmError::GenerationError(e.to_string()))?;
+            if let Some(error) = chunk.error {
+                return Err(LlmError::GenerationError(error).into());
+            }
+            if let Some(message) = chunk.message {
+                response_text.push_str(&message.content);
+            }
+            if chunk.done {
+                break;
+            }
+        }
+        if cancel.is_cancelled() {
+            return Ok(None);
+        }
 
-        let response_text = result.response.trim().to_string();
+        let response_text = response_text.trim().to_string();
         debug!("LLM応答生成完了: \"{}\"", response_text);
 
-        Ok(response_text)
+        Ok(Some(response_text))
     }
 
     /// Ollamaサーバーの接続確認
@@ -1,19 +1,34 @@
 mod audio;
+mod cancel;
+mod commands;
 mod config;
 mod llm;
+mod profile;
+mod speaker;
 mod stt;
+mod text;
+mod trigger;
 mod tts;
 mod wakeword;
 
 use anyhow::Result;
-use log::{error, info, warn};
+use log::{debug, error, info, warn};
+use std::sync::Arc;
+use std::time::Duration;
 
 use audio::{AudioCapture, AudioPlayback};
-use config::Config;
-use llm::OllamaLlm;
-use stt::WhisperStt;
-use tts::VoicevoxTts;
-use wakeword::WakewordDetector;
+use cancel::CancelToken;
+use config::{Config, PushToTalkMode, TriggerSourceKind};
+use llm::{Conversation, OllamaLlm};
+use profile::{Profile, ProfileRegistry};
+use speaker::{SpeakerIdentifier, SpeakerIdentity};
+use stt::{SpeechToText, StreamingTranscriber};
+use trigger::{TriggerHub, TriggerSource};
+use tts::TtsRouter;
+use wakeword::{ClipCollector, ClipKind, WakewordEvent, WakewordService, WakewordVerifier};
+
+/// キーボード・HTTPトリガーを確認する間隔
+const TRIGGER_POLL_INTERVAL: Duration = Duration::from_millis(50);
 
 fn main() -> Result<()> {
     // ログ初期化
@@ -25,121 +40,503 @@ fn main() -> Result<()> {
     let config = Config::load("config/settings.toml")?;
     info!("設定読み込み完了");
 
+    // サブコマンド
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.first().map(String::as_str) {
+        Some("enroll") => return commands::enroll::run(&config, &args[1..]),
+        Some("enroll-speaker") => return commands::enroll_speaker::run(&config, &args[1..]),
+        Some("eval-wakeword") => return commands::eval_wakeword::run(&config, &args[1..]),
+        Some("serve-stt") => return commands::serve_stt::run(&config, &args[1..]),
+        Some(other) => {
+            return Err(anyhow::anyhow!(
+                "不明なサブコマンドです: {} (利用可能: enroll, enroll-speaker, eval-wakeword, serve-stt)",
+                other
+            ))
+        }
+        None => {}
+    }
+
+    // ウェイクワードごとのペルソナ
+    let profiles = ProfileRegistry::from_config(&config)?;
+
     // 各コンポーネントの初期化とヘルスチェック
-    let llm = OllamaLlm::new(&config.llm)?;
+    // 割り込み時にワーカースレッドへ処理を任せて戻れるよう共有する
+    let llm = Arc::new(OllamaLlm::new(&config.llm)?);
     if !llm.health_check()? {
         error!("Ollamaサーバーに接続できません。Ollamaが起動していることを確認してください。");
         return Ok(());
     }
     info!("Ollama接続OK");
 
-    let tts = VoicevoxTts::new(&config.tts)?;
+    let tts = Arc::new(TtsRouter::new(&config.tts)?);
     if !tts.health_check()? {
         error!("VOICEVOXサーバーに接続できません。VOICEVOXが起動していることを確認してください。");
         return Ok(());
     }
     info!("VOICEVOX接続OK");
 
-    let stt = WhisperStt::new(&config.stt)?;
-    info!("Whisper初期化OK");
-
-    let mut wakeword_detector = WakewordDetector::new(&config.wakeword)?;
-    info!("ウェイクワード検出器初期化OK (Rustpotter)");
-
-    let capture = AudioCapture::new(
-        config.audio.sample_rate,
-        config.audio.input_gain,
-        config.audio.smoothing_alpha,
-        config.audio.relative_threshold_multiplier,
-        config.audio.calibration_duration,
-        config.audio.debounce_frames,
-    )?;
-    let playback = AudioPlayback::new()?;
+    let stt = stt::create_backend(&config.stt)?;
+    info!("音声認識初期化OK ({})", stt.name());
+
+    let mut wakeword_detector = wakeword::create_engine(&config.wakeword, &config.stt)?;
+    info!("ウェイクワード検出器初期化OK ({})", wakeword_detector.name());
+
+    let verifier = WakewordVerifier::from_config(&config.wakeword);
+    if verifier.is_some() {
+        info!("ウェイクワード二段階検証: 有効 (Whisper)");
+    }
+
+    let speaker_identifier = SpeakerIdentifier::from_config(&config.speaker)?;
+
+    // 再学習・閾値調整用のクリップ収集
+    let collector = ClipCollector::from_config(&config.wakeword)?;
+
+    let capture = AudioCapture::from_config(&config.audio)?;
+    let playback = AudioPlayback::from_config(&config.audio)?;
     info!("オーディオデバイス初期化OK");
 
     println!();
     println!("========================================");
     println!("  Smart Speaker Ready!");
-    println!("  Wakeword file: {}", config.wakeword.wakeword_path);
+    for entry in config.wakeword.entries() {
+        println!(
+            "  Wakeword: {} ({}) -> profile: {}",
+            entry.key(),
+            entry.path.as_deref().unwrap_or("whisper"),
+            profiles.for_keyword(&entry.key()).name
+        );
+    }
     println!("========================================");
 
+    // フォローアップ待機のチャイム
+    let follow_up_chime = if config.conversation.follow_up_chime {
+        Some(audio::chime::follow_up_chime(config.conversation.chime_volume)?)
+    } else {
+        None
+    };
+    let mut conversation = Conversation::new(config.conversation.max_history_turns);
+
+    // 応答再生中の自己検出を抑制
+    wakeword_detector.set_speaking_state(playback.speaking_state());
+
+    // ウェイクワード検出をバックグラウンドで開始（ウォームアップは起動時の1回のみ）
+    let wakeword_service = WakewordService::spawn(wakeword_detector, capture.reader(), collector.clone())?;
+
+    // キーボード・HTTPトリガー
+    let trigger_hub = TriggerHub::from_config(&config.trigger)?;
+    let wakeword_enabled = config.trigger.wakeword_enabled();
+    if !wakeword_enabled && trigger_hub.is_none() {
+        return Err(anyhow::anyhow!(
+            "[trigger] sources に有効な入力元がありません（wakeword, keyboard, http）"
+        ));
+    }
+    // 応答中の割り込みはウェイクワードが有効な場合のみ
+    let barge_in = config.conversation.barge_in && wakeword_enabled;
+
     // メインループ
     loop {
-        // ウェイクワード待機（Rustpotter）
-        match wakeword_detector.wait_for_wakeword(&capture) {
-            Ok(result) => {
-                info!("ウェイクワード \"{}\" 検出 (score: {:.2})", result.keyword, result.score);
-
-                // コマンドを録音
-                println!(">>> Listening for your command...");
-                match get_voice_command(&config, &capture, &stt) {
-                    Ok(Some(cmd)) => {
-                        // LLM応答を生成して再生
-                        if let Err(e) = process_command(&cmd, &llm, &tts, &playback) {
-                            error!("処理エラー: {}", e);
+        println!();
+        println!("========================================");
+        println!("  Waiting for {}...", describe_triggers(&config));
+        println!("========================================");
+        wakeword_service.set_meter(wakeword_enabled);
+
+        // トリガー待機（ウェイクワード / キーボード / HTTP）
+        let trigger = wait_for_trigger(&wakeword_service, trigger_hub.as_ref(), wakeword_enabled)?;
+        wakeword_service.set_meter(false);
+
+        let (event, push_to_talk) = match trigger {
+            Trigger::Wakeword(event) => {
+                println!();
+                println!("  >>> WAKEWORD DETECTED! <<<");
+                println!("  Keyword: \"{}\"", event.keyword);
+                println!("  Score: {:.3}", event.score);
+                info!("ウェイクワード \"{}\" 検出 (score: {:.2})", event.keyword, event.score);
+
+                // 二段階検証（Whisper）
+                if let Some(verifier) = &verifier {
+                    match verifier.verify(stt.as_ref(), &event) {
+                        Ok(decision) => {
+                            info!(
+                                "ウェイクワード検証: {} (transcript=\"{}\", phrase={:?}, similarity={:.2}, 検証{:.0}ms, 検出から{:.0}ms)",
+                                if decision.accepted { "受理" } else { "棄却" },
+                                decision.transcript,
+                                decision.matched_phrase,
+                                decision.similarity,
+                                decision.latency.as_secs_f32() * 1000.0,
+                                event.detected_at.elapsed().as_secs_f32() * 1000.0
+                            );
+                            if !decision.accepted {
+                                println!("  (rejected by verifier: \"{}\")", decision.transcript);
+                                collect_clip(
+                                    collector.as_ref(),
+                                    ClipKind::VerifierRejected,
+                                    &event,
+                                    Some(&decision.transcript),
+                                );
+                                wakeword_service.drain();
+                                if let Some(hub) = &trigger_hub {
+                                    hub.drain();
+                                }
+                                continue;
+                            }
+                        }
+                        Err(e) => {
+                            // 検証エラー時は検出を優先して受理する
+                            warn!("ウェイクワード検証エラー（検出を受理）: {}", e);
                         }
                     }
-                    Ok(None) => {
-                        warn!("コマンドを認識できませんでした。");
+                }
+                (Some(event), false)
+            }
+            Trigger::Manual(source) => {
+                println!();
+                println!("  >>> TRIGGERED ({:?}) <<<", source);
+                info!("手動トリガー: {:?}", source);
+                let push_to_talk = trigger_hub
+                    .as_ref()
+                    .is_some_and(|hub| hub.push_to_talk() != PushToTalkMode::Off);
+                (None, push_to_talk)
+            }
+        };
+
+        let mut profile = match &event {
+            Some(event) => profiles.for_keyword(&event.keyword),
+            None => profiles.default_profile(),
+        };
+        // 直近のウェイクワード検出（割り込みで更新、空コマンド時のクリップ収集用）
+        let mut trigger_event = event;
+
+        // トリガーごとに新しい会話を開始
+        conversation.clear();
+
+        // コマンドを録音
+        println!(">>> Listening for your command...");
+        let mut mode = match trigger_hub.as_ref() {
+            Some(hub) if push_to_talk => ListenMode::PushToTalk(hub),
+            _ => ListenMode::Command,
+        };
+        loop {
+            match get_voice_command(&config, &capture, stt.as_ref(), mode) {
+                Ok(Some(cmd)) => {
+                    // 応答中のウェイクワードで割り込めるようにする
+                    let cancel = CancelToken::new();
+                    if barge_in {
+                        wakeword_service.drain();
+                        wakeword_service.set_barge_in(Some(cancel.clone()));
                     }
-                    Err(e) => {
-                        error!("録音エラー: {}", e);
+
+                    // 話者識別
+                    let speaker = identify_speaker(speaker_identifier.as_ref(), &cmd.audio, config.audio.sample_rate);
+
+                    // LLM応答を生成して再生
+                    let result = process_command(
+                        &cmd.text,
+                        cmd.language.as_deref(),
+                        profile,
+                        speaker.as_ref(),
+                        &mut conversation,
+                        &llm,
+                        &tts,
+                        &playback,
+                        &cancel,
+                    );
+                    wakeword_service.set_barge_in(None);
+
+                    if let Err(e) = result {
+                        error!("処理エラー: {}", e);
+                        break;
                     }
+
+                    // 割り込み: 応答を打ち切り、すぐに次のコマンドを録音
+                    if cancel.is_cancelled() {
+                        if let Some(event) = wakeword_service.drain().pop() {
+                            println!();
+                            println!("  >>> BARGE-IN: \"{}\" (score: {:.3})", event.keyword, event.score);
+                            info!(
+                                "割り込み: \"{}\" (score: {:.2}, 検出から{:.0}ms)",
+                                event.keyword,
+                                event.score,
+                                event.detected_at.elapsed().as_secs_f32() * 1000.0
+                            );
+                            profile = profiles.for_keyword(&event.keyword);
+                            trigger_event = Some(event);
+                        }
+                        println!(">>> Listening for your command...");
+                        mode = ListenMode::Command;
+                        continue;
+                    }
+                }
+                Ok(None) => {
+                    if let ListenMode::FollowUp(_) = mode {
+                        info!("フォローアップ終了（発話なし）");
+                    } else {
+                        warn!("コマンドを認識できませんでした。");
+                        // 誤検出の可能性があるため収集
+                        if let Some(event) = &trigger_event {
+                            collect_clip(collector.as_ref(), ClipKind::EmptyCommand, event, None);
+                        }
+                    }
+                    break;
+                }
+                Err(e) => {
+                    error!("録音エラー: {}", e);
+                    break;
                 }
             }
-            Err(e) => {
-                error!("ウェイクワード検出エラー: {}", e);
-                std::thread::sleep(std::time::Duration::from_secs(1));
+
+            // フォローアップ: ウェイクワードなしで次の発話を待つ
+            if config.conversation.follow_up_seconds <= 0.0 {
+                break;
+            }
+            if let Some(chime) = &follow_up_chime {
+                if let Err(e) = playback.play_wav(chime) {
+                    warn!("チャイムの再生に失敗: {}", e);
+                }
             }
+            println!(
+                ">>> Listening for follow-up... ({:.0}s)",
+                config.conversation.follow_up_seconds
+            );
+            mode = ListenMode::FollowUp(config.conversation.follow_up_seconds);
+        }
+
+        // 処理中に発生した検出（コマンド中の発話や応答音声によるもの）やトリガーは破棄
+        let stale = wakeword_service.drain();
+        if !stale.is_empty() {
+            debug!("処理中の検出イベントを破棄: {} 件", stale.len());
+        }
+        if let Some(hub) = &trigger_hub {
+            hub.drain();
         }
     }
 }
 
+/// 聞き取り開始のトリガー
+enum Trigger {
+    /// ウェイクワード検出
+    Wakeword(WakewordEvent),
+    /// キーボード・HTTP
+    Manual(TriggerSource),
+}
+
+/// 次のトリガーを待機（ウェイクワードとキーボード・HTTPのうち早いもの）
+///
+/// ウェイクワードが無効な場合、検出イベントは読み捨てる。
+fn wait_for_trigger(
+    wakeword_service: &WakewordService,
+    trigger_hub: Option<&TriggerHub>,
+    wakeword_enabled: bool,
+) -> Result<Trigger> {
+    let Some(hub) = trigger_hub else {
+        return Ok(Trigger::Wakeword(wakeword_service.recv()?));
+    };
+
+    loop {
+        if let Some(source) = hub.try_recv_start() {
+            return Ok(Trigger::Manual(source));
+        }
+        if let Some(event) = wakeword_service.recv_timeout(TRIGGER_POLL_INTERVAL)? {
+            if wakeword_enabled {
+                return Ok(Trigger::Wakeword(event));
+            }
+            debug!("ウェイクワードトリガー無効のため検出を無視: \"{}\"", event.keyword);
+        }
+    }
+}
+
+/// 待機中に表示するトリガーの説明
+fn describe_triggers(config: &Config) -> String {
+    config
+        .trigger
+        .sources
+        .iter()
+        .map(|source| match source {
+            TriggerSourceKind::Wakeword => "wakeword",
+            TriggerSourceKind::Keyboard => "Enter",
+            TriggerSourceKind::Http => "HTTP trigger",
+        })
+        .collect::<Vec<_>>()
+        .join(" / ")
+}
+
+/// 認識した音声コマンド
+struct VoiceCommand {
+    /// 認識結果のテキスト
+    text: String,
+    /// 認識言語（Whisperの言語コード）
+    language: Option<String>,
+    /// 録音した音声（話者識別用）
+    audio: Vec<f32>,
+}
+
+/// コマンド録音の方式
+#[derive(Clone, Copy)]
+enum ListenMode<'a> {
+    /// 無音で録音を終了
+    Command,
+    /// 指定秒数以内に発話が始まらなければ終了（フォローアップ）
+    FollowUp(f32),
+    /// トリガーの停止信号まで録音（プッシュトゥトーク）
+    PushToTalk(&'a TriggerHub),
+}
+
 /// 音声コマンドを取得
+///
+/// フォローアップでは指定時間内に発話が始まらなければNoneを返す。
 fn get_voice_command(
     config: &Config,
     capture: &AudioCapture,
-    stt: &WhisperStt,
-) -> Result<Option<String>> {
-    let audio_data = capture.record_with_feedback(
-        config.audio.max_record_seconds,
-        config.audio.silence_threshold,
-        config.audio.silence_duration,
-    )?;
-
-    if audio_data.len() < (config.audio.sample_rate as usize / 2) {
-        return Ok(None);
-    }
+    stt: &dyn SpeechToText,
+    mode: ListenMode,
+) -> Result<Option<VoiceCommand>> {
+    let record = || -> Result<Vec<f32>> {
+        let audio_data = match mode {
+            ListenMode::FollowUp(timeout) => capture
+                .record_follow_up(
+                    config.audio.max_record_seconds,
+                    config.audio.silence_threshold,
+                    config.audio.silence_duration,
+                    timeout,
+                )?
+                .unwrap_or_default(),
+            ListenMode::PushToTalk(hub) => {
+                capture.record_until_stopped(config.trigger.push_to_talk_max_seconds, || hub.stop_requested())?
+            }
+            ListenMode::Command => capture.record_with_feedback(
+                config.audio.max_record_seconds,
+                config.audio.silence_threshold,
+                config.audio.silence_duration,
+            )?,
+        };
+        Ok(audio_data)
+    };
+    let min_samples = config.audio.sample_rate as usize / 2;
 
-    let start = std::time::Instant::now();
-    info!("音声認識中...");
-    let text = stt.transcribe(&audio_data)?;
-    let stt_time = start.elapsed();
-    info!("STT完了: {:.2}秒", stt_time.as_secs_f32());
+    let (audio_data, transcript) = if config.stt.streaming.enabled {
+        // 録音と並行して途中認識し、録音終了後すぐに確定する
+        let transcriber = StreamingTranscriber::new(stt, &config.stt.streaming);
+        let (audio_data, transcript) = transcriber.run(&capture.recording_monitor(), record, &|partial| {
+            info!("途中結果: \"{}\"", partial);
+        })?;
+        if audio_data.len() < min_samples {
+            return Ok(None);
+        }
+        (audio_data, transcript)
+    } else {
+        let audio_data = record()?;
+        if audio_data.len() < min_samples {
+            return Ok(None);
+        }
+
+        info!("音声認識中...");
+        let transcript = stt.transcribe(&audio_data)?;
+        info!("STT完了: {:.2}秒", transcript.processing_time.as_secs_f32());
+        (audio_data, transcript)
+    };
 
-    let text = text.trim().to_string();
+    info!(
+        "認識結果: language={}, avg_token_prob={}, max_no_speech_prob={}, segments={}",
+        transcript.language.as_deref().unwrap_or("-"),
+        transcript.avg_token_prob.map_or("-".to_string(), |p| format!("{:.2}", p)),
+        transcript.max_no_speech_prob().map_or("-".to_string(), |p| format!("{:.2}", p)),
+        transcript.segments.len()
+    );
+    if let Ok(json) = serde_json::to_string(&transcript) {
+        debug!("認識結果の詳細: {}", json);
+    }
 
-    if text.is_empty() {
+    if transcript.is_empty() {
         return Ok(None);
     }
+    let text = transcript.text.trim().to_string();
 
     println!(">>> You said: \"{}\"", text);
-    Ok(Some(text))
+    Ok(Some(VoiceCommand {
+        text,
+        language: transcript.language,
+        audio: audio_data,
+    }))
+}
+
+/// 検出イベントの音声をクリップとして保存（収集が無効なら何もしない）
+fn collect_clip(collector: Option<&ClipCollector>, kind: ClipKind, event: &WakewordEvent, transcript: Option<&str>) {
+    let Some(collector) = collector else {
+        return;
+    };
+    if let Err(e) = collector.save(kind, &event.keyword, event.score, &event.audio, event.timestamp, transcript) {
+        warn!("クリップの保存に失敗: {:#}", e);
+    }
+}
+
+/// コマンド音声の話者を識別（無効・識別失敗時はNone）
+fn identify_speaker(
+    identifier: Option<&SpeakerIdentifier>,
+    audio: &[f32],
+    sample_rate: u32,
+) -> Option<SpeakerIdentity> {
+    let identifier = identifier?;
+    let start = std::time::Instant::now();
+    match identifier.identify(audio, sample_rate) {
+        Ok(Some(identity)) => {
+            println!(">>> Speaker: {} ({:.2})", identity.name, identity.confidence);
+            info!(
+                "話者識別: user_id={}, confidence={:.3} ({:.0}ms)",
+                identity.user_id,
+                identity.confidence,
+                start.elapsed().as_secs_f32() * 1000.0
+            );
+            Some(identity)
+        }
+        Ok(None) => {
+            println!(">>> Speaker: unknown");
+            None
+        }
+        Err(e) => {
+            warn!("話者識別エラー: {}", e);
+            None
+        }
+    }
 }
 
 /// コマンドを処理してLLM応答を生成・再生
+///
+/// LLM・TTSはワーカースレッドで実行し、割り込み（キャンセル）があれば完了を待たずに戻る。
+/// 最後まで再生できた応答だけを会話履歴に残す。
+#[allow(clippy::too_many_arguments)]
 fn process_command(
     command: &str,
-    llm: &OllamaLlm,
-    tts: &VoicevoxTts,
+    language: Option<&str>,
+    profile: &Profile,
+    speaker: Option<&SpeakerIdentity>,
+    conversation: &mut Conversation,
+    llm: &Arc<OllamaLlm>,
+    tts: &Arc<TtsRouter>,
     playback: &AudioPlayback,
+    cancel: &CancelToken,
 ) -> Result<()> {
-    println!(">>> Processing: \"{}\"", command);
+    println!(">>> Processing: \"{}\" (profile: {})", command, profile.name);
 
     // LLM: テキスト→応答
     let start = std::time::Instant::now();
-    info!("LLM応答生成中...");
-    let response = llm.generate(command)?;
+    info!("LLM応答生成中... (model={}, language={:?})", profile.model, language);
+    let base_prompt = profile.system_prompt_for(language);
+    let system_prompt = match speaker {
+        Some(identity) => format!("{}\n\n{}", base_prompt, identity.prompt_context()),
+        None => base_prompt.to_string(),
+    };
+    let response = {
+        let llm = Arc::clone(llm);
+        let history = conversation.history().to_vec();
+        let prompt = command.to_string();
+        let model = profile.model.clone();
+        let task_cancel = cancel.clone();
+        cancel.run("llm", move || llm.chat_with(&history, &prompt, &model, &system_prompt, &task_cancel))?
+    };
+    let Some(response) = response.flatten() else {
+        info!("割り込みのため応答生成を中断 ({:.2}秒)", start.elapsed().as_secs_f32());
+        return Ok(());
+    };
     let llm_time = start.elapsed();
     info!("LLM完了: {:.2}秒", llm_time.as_secs_f32());
     println!(">>> Response: \"{}\"", response);
@@ -147,13 +544,27 @@ fn process_command(
     // TTS: 応答→音声
     let start = std::time::Instant::now();
     info!("音声合成中...");
-    let audio_response = tts.synthesize(&response)?;
+    let audio_response = {
+        let tts = Arc::clone(tts);
+        let text = response.clone();
+        let speaker_id = profile.speaker_id;
+        let language = language.map(str::to_string);
+        cancel.run("tts", move || tts.synthesize(&text, speaker_id, language.as_deref()))?
+    };
+    let Some(audio_response) = audio_response else {
+        info!("割り込みのため音声合成を中断 ({:.2}秒)", start.elapsed().as_secs_f32());
+        return Ok(());
+    };
     let tts_time = start.elapsed();
     info!("TTS完了: {:.2}秒 ({} bytes)", tts_time.as_secs_f32(), audio_response.len());
 
-    // 音声再生
+    // 音声再生（割り込みで中断）
     info!("応答を再生中...");
-    playback.play_wav(&audio_response)?;
+    if !playback.play_wav_cancellable(&audio_response, cancel)? {
+        return Ok(());
+    }
+    // 聞き終えた応答だけをフォローアップの文脈にする
+    conversation.push_turn(command, &response);
 
     println!();
     Ok(())
@@ -0,0 +1,109 @@
+use anyhow::Result;
+use log::info;
+use std::collections::HashMap;
+
+use crate::config::Config;
+
+/// ウェイクワードに紐づくペルソナ（LLMモデル・システムプロンプト・話者）
+#[derive(Debug, Clone)]
+pub struct Profile {
+    /// プロファイル名（デフォルトは"default"）
+    pub name: String,
+    /// Ollamaモデル名
+    pub model: String,
+    /// システムプロンプト
+    pub system_prompt: String,
+    /// 認識言語ごとのシステムプロンプト
+    pub system_prompts: HashMap<String, String>,
+    /// VOICEVOXの話者ID
+    pub speaker_id: i32,
+}
+
+impl Profile {
+    /// 認識言語に応じたシステムプロンプト（言語別の指定がなければ既定のもの）
+    pub fn system_prompt_for(&self, language: Option<&str>) -> &str {
+        language
+            .and_then(|language| self.system_prompts.get(language))
+            .unwrap_or(&self.system_prompt)
+    }
+}
+
+/// キーワード名からプロファイルを引くためのレジストリ
+pub struct ProfileRegistry {
+    default: Profile,
+    by_keyword: HashMap<String, Profile>,
+}
+
+impl ProfileRegistry {
+    /// 設定からレジストリを構築
+    ///
+    /// 存在しないプロファイル名を参照しているウェイクワードがあればエラー。
+    pub fn from_config(config: &Config) -> Result<Self> {
+        let default = Profile {
+            name: "default".to_string(),
+            model: config.llm.model.clone(),
+            system_prompt: config.llm.system_prompt.clone(),
+            system_prompts: config.llm.system_prompts.clone(),
+            speaker_id: config.tts.speaker_id,
+        };
+
+        let mut by_keyword = HashMap::new();
+        for entry in config.wakeword.entries() {
+            let Some(profile_name) = &entry.profile else {
+                continue;
+            };
+            let profile_config = config.profiles.get(profile_name).ok_or_else(|| {
+                anyhow::anyhow!(
+                    "ウェイクワード \"{}\" が存在しないプロファイルを参照しています: {}",
+                    entry.key(),
+                    profile_name
+                )
+            })?;
+
+            // 独自のシステムプロンプトを持つペルソナには[llm]の言語別プロンプトを引き継がない
+            let mut system_prompts = if profile_config.system_prompt.is_some() {
+                HashMap::new()
+            } else {
+                default.system_prompts.clone()
+            };
+            system_prompts.extend(profile_config.system_prompts.clone());
+
+            let profile = Profile {
+                name: profile_name.clone(),
+                model: profile_config
+                    .model
+                    .clone()
+                    .unwrap_or_else(|| default.model.clone()),
+                system_prompt: profile_config
+                    .system_prompt
+                    .clone()
+                    .unwrap_or_else(|| default.system_prompt.clone()),
+                system_prompts,
+                speaker_id: profile_config.speaker_id.unwrap_or(default.speaker_id),
+            };
+
+            info!(
+                "プロファイル割り当て: keyword=\"{}\" -> profile=\"{}\" (model={}, speaker_id={})",
+                entry.key(),
+                profile.name,
+                profile.model,
+                profile.speaker_id
+            );
+            by_keyword.insert(entry.key(), profile);
+        }
+
+        Ok(Self { default, by_keyword })
+    }
+
+    /// デフォルトのプロファイル（[llm]/[tts]の設定、キーボード等のトリガー用）
+    pub fn default_profile(&self) -> &Profile {
+        &self.default
+    }
+
+    /// 検出されたキーワードに対応するプロファイルを取得
+    ///
+    /// プロファイル未設定のキーワードはデフォルト（[llm]/[tts]の設定）を返す。
+    pub fn for_keyword(&self, keyword: &str) -> &Profile {
+        self.by_keyword.get(keyword).unwrap_or(&self.default)
+    }
+}
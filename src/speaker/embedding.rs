@@ -0,0 +1,250 @@
+use anyhow::Result;
+
+use crate::audio::{self, wav};
+
+/// 話者埋め込み（声の特徴ベクトル）の抽出器
+///
+/// 登録と照合で同じ抽出器を使う必要がある（`name`を話者プロファイルに保存して確認する）。
+pub trait EmbeddingExtractor: Send {
+    /// 抽出器の識別名（プロファイルとの整合性確認用）
+    fn name(&self) -> &str;
+
+    /// 音声から話者埋め込みを抽出
+    ///
+    /// # Arguments
+    /// * `audio` - モノラルf32音声
+    /// * `sample_rate` - サンプルレート
+    fn extract(&self, audio: &[f32], sample_rate: u32) -> Result<Vec<f32>>;
+}
+
+// === MFCC抽出設定 ===
+/// 解析サンプルレート
+const MFCC_SAMPLE_RATE: u32 = 16000;
+/// フレーム長（25ms @ 16kHz）
+const MFCC_FRAME_SIZE: usize = 400;
+/// フレームシフト（10ms @ 16kHz）
+const MFCC_HOP_SIZE: usize = 160;
+/// FFT点数
+const MFCC_FFT_SIZE: usize = 512;
+/// メルフィルタバンク数
+const MFCC_NUM_FILTERS: usize = 40;
+/// MFCC係数の数（c0を除く）
+const MFCC_NUM_COEFFS: usize = 20;
+/// プリエンファシス係数
+const MFCC_PRE_EMPHASIS: f32 = 0.97;
+/// 有声フレームとみなすエネルギー（最大フレームからのdB差）
+const MFCC_VOICED_RANGE_DB: f32 = 30.0;
+/// 埋め込みに必要な最小有声フレーム数（約0.5秒）
+const MFCC_MIN_VOICED_FRAMES: usize = 50;
+
+/// MFCC統計量による軽量な話者埋め込み（CPUのみ、追加モデル不要）
+///
+/// 有声フレームのMFCCの平均と標準偏差を連結し、L2正規化したベクトルを返す。
+/// 精度は専用の話者認識モデルに劣るが、家庭内の数人程度の識別を想定している。
+pub struct MfccExtractor {
+    filterbank: Vec<Vec<f32>>,
+    window: Vec<f32>,
+}
+
+impl MfccExtractor {
+    pub fn new() -> Self {
+        let window = (0..MFCC_FRAME_SIZE)
+            .map(|i| {
+                0.54 - 0.46 * (2.0 * std::f32::consts::PI * i as f32 / (MFCC_FRAME_SIZE - 1) as f32).cos()
+            })
+            .collect();
+
+        Self {
+            filterbank: mel_filterbank(MFCC_NUM_FILTERS, MFCC_FFT_SIZE, MFCC_SAMPLE_RATE),
+            window,
+        }
+    }
+
+    /// 1フレームのMFCC（c1〜c20）と対数エネルギーを計算
+    fn frame_mfcc(&self, frame: &[f32]) -> (Vec<f32>, f32) {
+        let mut re = vec![0.0_f32; MFCC_FFT_SIZE];
+        let mut im = vec![0.0_f32; MFCC_FFT_SIZE];
+        for (i, (&sample, &w)) in frame.iter().zip(&self.window).enumerate() {
+            re[i] = sample * w;
+        }
+        fft(&mut re, &mut im);
+
+        let power: Vec<f32> = (0..=MFCC_FFT_SIZE / 2)
+            .map(|k| (re[k] * re[k] + im[k] * im[k]) / MFCC_FFT_SIZE as f32)
+            .collect();
+        let energy = 10.0 * power.iter().sum::<f32>().max(1e-10).log10();
+
+        let log_mel: Vec<f32> = self
+            .filterbank
+            .iter()
+            .map(|filter| filter.iter().zip(&power).map(|(f, p)| f * p).sum::<f32>().max(1e-10).ln())
+            .collect();
+
+        // DCT-II（c0は音量に依存するため除外）
+        let n = log_mel.len() as f32;
+        let coeffs = (1..=MFCC_NUM_COEFFS)
+            .map(|c| {
+                log_mel
+                    .iter()
+                    .enumerate()
+                    .map(|(m, &v)| v * (std::f32::consts::PI * c as f32 * (m as f32 + 0.5) / n).cos())
+                    .sum::<f32>()
+            })
+            .collect();
+
+        (coeffs, energy)
+    }
+}
+
+impl EmbeddingExtractor for MfccExtractor {
+    fn name(&self) -> &str {
+        "mfcc-stats"
+    }
+
+    fn extract(&self, audio: &[f32], sample_rate: u32) -> Result<Vec<f32>> {
+        let audio = audio::resample(audio, sample_rate, MFCC_SAMPLE_RATE);
+
+        // プリエンファシス
+        let emphasized: Vec<f32> = std::iter::once(audio.first().copied().unwrap_or(0.0))
+            .chain(audio.windows(2).map(|w| w[1] - MFCC_PRE_EMPHASIS * w[0]))
+            .collect();
+
+        let frames: Vec<(Vec<f32>, f32)> = emphasized
+            .windows(MFCC_FRAME_SIZE)
+            .step_by(MFCC_HOP_SIZE)
+            .map(|frame| self.frame_mfcc(frame))
+            .collect();
+
+        // 有声フレームのみ使用（無音・ノイズ区間を除外）
+        let max_energy = frames.iter().map(|(_, e)| *e).fold(f32::MIN, f32::max);
+        let voiced: Vec<&Vec<f32>> = frames
+            .iter()
+            .filter(|(_, e)| *e >= max_energy - MFCC_VOICED_RANGE_DB)
+            .map(|(c, _)| c)
+            .collect();
+
+        if voiced.len() < MFCC_MIN_VOICED_FRAMES {
+            return Err(anyhow::anyhow!(
+                "発話が短すぎて話者の特徴を抽出できません（有声フレーム {} < {}）",
+                voiced.len(),
+                MFCC_MIN_VOICED_FRAMES
+            ));
+        }
+
+        let count = voiced.len() as f32;
+        let mut mean = vec![0.0_f32; MFCC_NUM_COEFFS];
+        for coeffs in &voiced {
+            for (m, c) in mean.iter_mut().zip(coeffs.iter()) {
+                *m += c / count;
+            }
+        }
+        let mut std_dev = vec![0.0_f32; MFCC_NUM_COEFFS];
+        for coeffs in &voiced {
+            for ((s, c), m) in std_dev.iter_mut().zip(coeffs.iter()).zip(&mean) {
+                *s += (c - m).powi(2) / count;
+            }
+        }
+        std_dev.iter_mut().for_each(|s| *s = s.sqrt());
+
+        let mut embedding: Vec<f32> = mean.into_iter().chain(std_dev).collect();
+        normalize(&mut embedding);
+        Ok(embedding)
+    }
+}
+
+/// WAVファイルから埋め込みを抽出（登録用）
+pub fn extract_from_wav(extractor: &dyn EmbeddingExtractor, path: &str) -> Result<Vec<f32>> {
+    let (audio, sample_rate) = wav::read_wav_mono(path)?;
+    extractor.extract(&audio, sample_rate)
+}
+
+/// L2正規化
+pub fn normalize(vector: &mut [f32]) {
+    let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        vector.iter_mut().for_each(|v| *v /= norm);
+    }
+}
+
+/// コサイン類似度（-1.0〜1.0）
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|v| v * v).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    dot / (norm_a * norm_b)
+}
+
+/// メルフィルタバンク（三角フィルタ、各フィルタはFFTビンの重み）
+fn mel_filterbank(num_filters: usize, fft_size: usize, sample_rate: u32) -> Vec<Vec<f32>> {
+    let hz_to_mel = |hz: f32| 2595.0 * (1.0 + hz / 700.0).log10();
+    let mel_to_hz = |mel: f32| 700.0 * (10f32.powf(mel / 2595.0) - 1.0);
+
+    let num_bins = fft_size / 2 + 1;
+    let max_mel = hz_to_mel(sample_rate as f32 / 2.0);
+    let bin_points: Vec<f32> = (0..num_filters + 2)
+        .map(|i| {
+            let hz = mel_to_hz(max_mel * i as f32 / (num_filters + 1) as f32);
+            hz * fft_size as f32 / sample_rate as f32
+        })
+        .collect();
+
+    (0..num_filters)
+        .map(|f| {
+            let (left, center, right) = (bin_points[f], bin_points[f + 1], bin_points[f + 2]);
+            (0..num_bins)
+                .map(|bin| {
+                    let bin = bin as f32;
+                    if bin <= left || bin >= right {
+                        0.0
+                    } else if bin <= center {
+                        (bin - left) / (center - left)
+                    } else {
+                        (right - bin) / (right - center)
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// 基数2のインプレースFFT（長さは2のべき乗）
+fn fft(re: &mut [f32], im: &mut [f32]) {
+    let n = re.len();
+
+    // ビット反転並べ替え
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            re.swap(i, j);
+            im.swap(i, j);
+        }
+    }
+
+    let mut len = 2;
+    while len <= n {
+        let angle = -2.0 * std::f32::consts::PI / len as f32;
+        for start in (0..n).step_by(len) {
+            for k in 0..len / 2 {
+                let (sin, cos) = (angle * k as f32).sin_cos();
+                let a = start + k;
+                let b = a + len / 2;
+                let tr = re[b] * cos - im[b] * sin;
+                let ti = re[b] * sin + im[b] * cos;
+                re[b] = re[a] - tr;
+                im[b] = im[a] - ti;
+                re[a] += tr;
+                im[a] += ti;
+            }
+        }
+        len <<= 1;
+    }
+}
@@ -0,0 +1,152 @@
+use anyhow::Result;
+use log::{debug, info, warn};
+
+use super::embedding::{cosine_similarity, normalize, EmbeddingExtractor, MfccExtractor};
+use super::store::SpeakerStore;
+use crate::config::SpeakerConfig;
+
+/// 話者識別の結果
+#[derive(Debug, Clone)]
+pub struct SpeakerIdentity {
+    /// ユーザーID
+    pub user_id: String,
+    /// 表示名
+    pub name: String,
+    /// 信頼度（登録音声とのコサイン類似度、0.0〜1.0）
+    pub confidence: f32,
+    /// LLMへ伝える補足（プロンプトに含めるだけで、操作の実行可否は制御しない）
+    pub prompt_hints: Vec<String>,
+}
+
+impl SpeakerIdentity {
+    /// LLMのシステムプロンプトに追加する話者情報
+    pub fn prompt_context(&self) -> String {
+        let mut context = format!(
+            "現在話しているユーザーは「{}」（ユーザーID: {}）です。",
+            self.name, self.user_id
+        );
+        if !self.prompt_hints.is_empty() {
+            context.push_str(&format!("このユーザーについての補足: {}", self.prompt_hints.join(", ")));
+        }
+        context
+    }
+}
+
+/// 登録話者1人分の照合用データ
+struct EnrolledSpeaker {
+    id: String,
+    name: String,
+    prompt_hints: Vec<String>,
+    /// 登録埋め込みの平均（L2正規化済み）
+    centroid: Vec<f32>,
+}
+
+/// 設定名から埋め込み抽出器を生成
+pub fn create_extractor(name: &str) -> Result<Box<dyn EmbeddingExtractor>> {
+    match name {
+        "mfcc" => Ok(Box::new(MfccExtractor::new())),
+        other => Err(anyhow::anyhow!("不明な話者埋め込み抽出器です: {} (利用可能: mfcc)", other)),
+    }
+}
+
+/// 登録済み話者との照合による話者識別
+pub struct SpeakerIdentifier {
+    extractor: Box<dyn EmbeddingExtractor>,
+    speakers: Vec<EnrolledSpeaker>,
+    min_confidence: f32,
+}
+
+impl SpeakerIdentifier {
+    /// 設定から識別器を生成（無効ならNone）
+    pub fn from_config(config: &SpeakerConfig) -> Result<Option<Self>> {
+        if !config.enabled {
+            return Ok(None);
+        }
+
+        let extractor = create_extractor(&config.extractor)?;
+        let store = SpeakerStore::load(&config.profiles_path)?;
+
+        let mut speakers = Vec::new();
+        for profile in store.speakers() {
+            if profile.extractor != extractor.name() {
+                warn!(
+                    "話者 \"{}\" は別の抽出器で登録されているためスキップします ({} != {})。再登録してください",
+                    profile.id,
+                    profile.extractor,
+                    extractor.name()
+                );
+                continue;
+            }
+            let Some(dim) = profile.embeddings.first().map(Vec::len) else {
+                continue;
+            };
+
+            let mut centroid = vec![0.0_f32; dim];
+            for embedding in &profile.embeddings {
+                for (c, v) in centroid.iter_mut().zip(embedding) {
+                    *c += v;
+                }
+            }
+            normalize(&mut centroid);
+
+            speakers.push(EnrolledSpeaker {
+                id: profile.id.clone(),
+                name: profile.name.clone(),
+                prompt_hints: profile.prompt_hints.clone(),
+                centroid,
+            });
+        }
+
+        info!(
+            "話者識別: 有効 (extractor={}, 登録話者={} 人, min_confidence={})",
+            extractor.name(),
+            speakers.len(),
+            config.min_confidence
+        );
+        if speakers.is_empty() {
+            warn!("話者が登録されていません（enroll-speaker で登録してください）");
+        }
+
+        Ok(Some(Self {
+            extractor,
+            speakers,
+            min_confidence: config.min_confidence,
+        }))
+    }
+
+    /// コマンド音声の話者を識別（信頼度が閾値未満ならNone）
+    pub fn identify(&self, audio: &[f32], sample_rate: u32) -> Result<Option<SpeakerIdentity>> {
+        if self.speakers.is_empty() {
+            return Ok(None);
+        }
+
+        let embedding = self.extractor.extract(audio, sample_rate)?;
+
+        let mut best: Option<(&EnrolledSpeaker, f32)> = None;
+        for speaker in &self.speakers {
+            let score = cosine_similarity(&embedding, &speaker.centroid);
+            debug!("話者照合: id={} score={:.3}", speaker.id, score);
+            if best.is_none_or(|(_, s)| score > s) {
+                best = Some((speaker, score));
+            }
+        }
+
+        let Some((speaker, confidence)) = best else {
+            return Ok(None);
+        };
+        if confidence < self.min_confidence {
+            info!(
+                "話者を特定できませんでした（最も近い: {} {:.3} < {}）",
+                speaker.id, confidence, self.min_confidence
+            );
+            return Ok(None);
+        }
+
+        Ok(Some(SpeakerIdentity {
+            user_id: speaker.id.clone(),
+            name: speaker.name.clone(),
+            confidence,
+            prompt_hints: speaker.prompt_hints.clone(),
+        }))
+    }
+}
@@ -0,0 +1,7 @@
+mod embedding;
+mod identifier;
+mod store;
+
+pub use embedding::{extract_from_wav, EmbeddingExtractor};
+pub use identifier::{create_extractor, SpeakerIdentifier, SpeakerIdentity};
+pub use store::{SpeakerProfile, SpeakerStore};
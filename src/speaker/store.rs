@@ -0,0 +1,91 @@
+use anyhow::{Context, Result};
+use log::info;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 登録済み話者1人分のプロファイル
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpeakerProfile {
+    /// ユーザーID（LLMプロンプトに使う）
+    pub id: String,
+    /// 表示名
+    pub name: String,
+    /// LLMのプロンプトに添える補足（例: 「買い物リストの編集可」）
+    ///
+    /// プロンプトに含めるだけで、操作の実行可否は制御しない。
+    #[serde(default, alias = "permissions")]
+    pub prompt_hints: Vec<String>,
+    /// 埋め込みを抽出した抽出器の識別名
+    pub extractor: String,
+    /// 登録発話ごとの埋め込み
+    pub embeddings: Vec<Vec<f32>>,
+}
+
+/// ファイル形式（JSON）
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct StoreFile {
+    speakers: Vec<SpeakerProfile>,
+}
+
+/// 話者プロファイルの保存先（JSONファイル）
+pub struct SpeakerStore {
+    path: PathBuf,
+    speakers: Vec<SpeakerProfile>,
+}
+
+impl SpeakerStore {
+    /// ファイルから読み込む（ファイルがなければ空）
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        if !path.exists() {
+            return Ok(Self {
+                path,
+                speakers: Vec::new(),
+            });
+        }
+
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("話者プロファイルの読み込みに失敗: {}", path.display()))?;
+        let file: StoreFile = serde_json::from_str(&content)
+            .with_context(|| format!("話者プロファイルのパースに失敗: {}", path.display()))?;
+
+        Ok(Self {
+            path,
+            speakers: file.speakers,
+        })
+    }
+
+    /// ファイルへ保存
+    pub fn save(&self) -> Result<()> {
+        if let Some(parent) = self.path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+        let file = StoreFile {
+            speakers: self.speakers.clone(),
+        };
+        let content = serde_json::to_string_pretty(&file)?;
+        fs::write(&self.path, content)
+            .with_context(|| format!("話者プロファイルの保存に失敗: {}", self.path.display()))?;
+        info!("話者プロファイルを保存: {} ({} 人)", self.path.display(), self.speakers.len());
+        Ok(())
+    }
+
+    /// 登録済みの話者一覧
+    pub fn speakers(&self) -> &[SpeakerProfile] {
+        &self.speakers
+    }
+
+    /// 話者を追加（同じIDがあれば置き換え）
+    pub fn upsert(&mut self, profile: SpeakerProfile) {
+        self.speakers.retain(|s| s.id != profile.id);
+        self.speakers.push(profile);
+    }
+
+    /// 話者を削除（削除した場合true）
+    pub fn remove(&mut self, id: &str) -> bool {
+        let before = self.speakers.len();
+        self.speakers.retain(|s| s.id != id);
+        self.speakers.len() != before
+    }
+}
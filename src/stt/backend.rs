@@ -0,0 +1,35 @@
+use anyhow::Result;
+
+use super::http::HttpStt;
+use super::transcript::Transcript;
+use super::whisper::WhisperStt;
+use crate::config::{SttBackendKind, SttConfig};
+
+/// 音声認識エンジンの共通インターフェース
+///
+/// ストリーミング認識では認識スレッドから呼ばれるため`Sync`が必要。
+pub trait SpeechToText: Send + Sync {
+    /// ログ表示用のバックエンド名
+    fn name(&self) -> &'static str;
+
+    /// 音声データを認識（ハルシネーション除去・語彙補正あり）
+    ///
+    /// # Arguments
+    /// * `audio` - 音声データ（f32, 16kHz, モノラル, -1.0〜1.0の範囲）
+    fn transcribe(&self, audio: &[f32]) -> Result<Transcript>;
+
+    /// 初期プロンプトを指定して音声データを認識（ハルシネーション除去・語彙補正なし）
+    ///
+    /// # Arguments
+    /// * `audio` - 音声データ（f32, 16kHz, モノラル, -1.0〜1.0の範囲）
+    /// * `prompt` - Whisperに与える初期プロンプト（語彙の誘導用）
+    fn transcribe_with_prompt(&self, audio: &[f32], prompt: Option<&str>) -> Result<Transcript>;
+}
+
+/// 設定に応じた音声認識エンジンを生成
+pub fn create_backend(config: &SttConfig) -> Result<Box<dyn SpeechToText>> {
+    Ok(match config.backend {
+        SttBackendKind::Whisper => Box::new(WhisperStt::new(config)?),
+        SttBackendKind::Http => Box::new(HttpStt::new(config)?),
+    })
+}
@@ -0,0 +1,148 @@
+use log::info;
+use serde::Serialize;
+use std::fmt;
+
+use super::transcript::Transcript;
+use crate::config::HallucinationConfig;
+use crate::text::kana;
+
+/// 繰り返しとみなす単位の最小・最大の長さ（文字数）
+const MIN_REPEAT_UNIT: usize = 2;
+const MAX_REPEAT_UNIT: usize = 20;
+
+/// 認識結果を棄却した理由
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "reason", rename_all = "snake_case")]
+pub enum Rejection {
+    /// 既知のハルシネーションフレーズのみ
+    Blocklisted,
+    /// 同じ語句の繰り返し（ループ）
+    Repetition { unit: String, count: usize },
+    /// トークンの平均確率が低い
+    LowConfidence { avg_token_prob: f32 },
+    /// 音声の長さに対して文字数が多すぎる
+    TooLong { chars_per_second: f32 },
+}
+
+impl fmt::Display for Rejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Rejection::Blocklisted => write!(f, "既知のハルシネーションフレーズ"),
+            Rejection::Repetition { unit, count } => write!(f, "繰り返し (\"{}\" x{})", unit, count),
+            Rejection::LowConfidence { avg_token_prob } => {
+                write!(f, "平均トークン確率が低い ({:.2})", avg_token_prob)
+            }
+            Rejection::TooLong { chars_per_second } => {
+                write!(f, "音声の長さに対して文字数が多い ({:.1}文字/秒)", chars_per_second)
+            }
+        }
+    }
+}
+
+/// 無音・雑音に対するWhisperのハルシネーションを検出するフィルタ
+pub struct HallucinationFilter {
+    /// 既知のハルシネーションフレーズ（読みキー）
+    blocklist: Vec<String>,
+    max_repeats: usize,
+    min_avg_token_prob: f32,
+    max_chars_per_second: f32,
+}
+
+impl HallucinationFilter {
+    /// 設定からフィルタを生成（無効ならNone）
+    pub fn from_config(config: &HallucinationConfig) -> Option<Self> {
+        if !config.enabled {
+            return None;
+        }
+        Some(Self {
+            blocklist: config
+                .blocklist
+                .iter()
+                .map(|phrase| kana::to_reading_key(phrase))
+                .filter(|key| !key.is_empty())
+                .collect(),
+            max_repeats: config.max_repeats,
+            min_avg_token_prob: config.min_avg_token_prob,
+            max_chars_per_second: config.max_chars_per_second,
+        })
+    }
+
+    /// 認識結果を検査し、ハルシネーションと判定した場合は理由を返す
+    ///
+    /// # Arguments
+    /// * `text` - 認識結果
+    /// * `avg_token_prob` - トークンの平均確率（取得できない場合はNone）
+    /// * `speech_seconds` - 認識した音声（無音区間除去後）の長さ
+    pub fn check(&self, text: &str, avg_token_prob: Option<f32>, speech_seconds: f32) -> Option<Rejection> {
+        let key = kana::to_reading_key(text);
+        if key.is_empty() {
+            return None;
+        }
+
+        if self.is_blocklisted(&key) {
+            return Some(Rejection::Blocklisted);
+        }
+
+        if let Some((unit, count)) = self.find_repetition(&key) {
+            return Some(Rejection::Repetition { unit, count });
+        }
+
+        if let Some(avg_token_prob) = avg_token_prob.filter(|&p| p < self.min_avg_token_prob) {
+            return Some(Rejection::LowConfidence { avg_token_prob });
+        }
+
+        if speech_seconds > 0.0 {
+            let chars_per_second = key.chars().count() as f32 / speech_seconds;
+            if chars_per_second > self.max_chars_per_second {
+                return Some(Rejection::TooLong { chars_per_second });
+            }
+        }
+
+        None
+    }
+
+    /// 認識結果を検査し、棄却した場合はテキストを空にして理由を記録する
+    pub fn apply(&self, transcript: &mut Transcript) {
+        if let Some(rejection) = self.check(&transcript.text, transcript.avg_token_prob, transcript.speech_seconds) {
+            info!("認識結果を棄却: {} (\"{}\")", rejection, transcript.text);
+            transcript.text.clear();
+            transcript.rejection = Some(rejection);
+        }
+    }
+
+    /// 認識結果がブロックリストのフレーズだけでできているか
+    fn is_blocklisted(&self, key: &str) -> bool {
+        let mut rest = key.to_string();
+        for phrase in &self.blocklist {
+            rest = rest.replace(phrase.as_str(), "");
+        }
+        rest.is_empty()
+    }
+
+    /// 同じ語句がmax_repeats回以上連続している箇所を探す
+    fn find_repetition(&self, key: &str) -> Option<(String, usize)> {
+        if self.max_repeats < 2 {
+            return None;
+        }
+        let chars: Vec<char> = key.chars().collect();
+        let max_unit = MAX_REPEAT_UNIT.min(chars.len() / self.max_repeats);
+        for unit_len in MIN_REPEAT_UNIT..=max_unit {
+            for start in 0..chars.len() {
+                let unit = &chars[start..(start + unit_len).min(chars.len())];
+                if unit.len() < unit_len {
+                    break;
+                }
+                let mut count = 1;
+                let mut pos = start + unit_len;
+                while pos + unit_len <= chars.len() && chars[pos..pos + unit_len] == *unit {
+                    count += 1;
+                    pos += unit_len;
+                }
+                if count >= self.max_repeats {
+                    return Some((unit.iter().collect(), count));
+                }
+            }
+        }
+        None
+    }
+}
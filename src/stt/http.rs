@@ -0,0 +1,211 @@
+use anyhow::Result;
+use log::{debug, info};
+use reqwest::blocking::multipart::{Form, Part};
+use reqwest::blocking::Client;
+use serde::Deserialize;
+use std::time::{Duration, Instant};
+use thiserror::Error;
+
+use super::backend::SpeechToText;
+use super::postprocess::PostProcessor;
+use super::transcript::{Transcript, TranscriptSegment};
+use super::whisper::WhisperStt;
+use crate::audio::wav;
+use crate::config::SttConfig;
+
+/// 入力サンプルレート
+const SAMPLE_RATE: u32 = 16000;
+
+/// HTTPバックエンドに関するエラー
+#[derive(Debug, Error)]
+pub enum HttpSttError {
+    #[error("文字起こしサーバーへの接続に失敗: {0}")]
+    ConnectionError(String),
+
+    #[error("文字起こしに失敗: {0}")]
+    TranscriptionError(String),
+}
+
+/// 文字起こしAPIレスポンス（verbose_json）
+#[derive(Debug, Deserialize)]
+struct TranscriptionResponse {
+    text: String,
+    /// 言語（サーバーにより"ja"または"japanese"）
+    #[serde(default)]
+    language: Option<String>,
+    #[serde(default)]
+    segments: Vec<ResponseSegment>,
+}
+
+/// 文字起こしAPIレスポンスのセグメント
+#[derive(Debug, Deserialize)]
+struct ResponseSegment {
+    text: String,
+    start: f32,
+    end: f32,
+    #[serde(default)]
+    avg_logprob: Option<f32>,
+    #[serde(default)]
+    no_speech_prob: Option<f32>,
+}
+
+/// OpenAI互換の文字起こしサーバー（whisper.cpp server、serve-sttなど）を使用した音声認識エンジン
+///
+/// VAD・音量正規化はクライアント側で行い、音声区間だけをWAVで送信する。
+/// ハルシネーション除去・語彙補正もクライアント側の設定で行う。
+pub struct HttpStt {
+    client: Client,
+    url: String,
+    model: String,
+    language: String,
+    temperature: f32,
+    no_speech_thold: f32,
+    api_key: Option<String>,
+    postprocess: PostProcessor,
+}
+
+impl HttpStt {
+    /// 設定からHttpSttインスタンスを生成
+    ///
+    /// # Arguments
+    /// * `config` - STT設定（`[stt.http]`を使用）
+    pub fn new(config: &SttConfig) -> Result<Self> {
+        let http = &config.http;
+        let url = format!("{}/v1/audio/transcriptions", http.endpoint.trim_end_matches('/'));
+        info!("HTTP文字起こし初期化: url={}, model={}", url, http.model);
+
+        let client = Client::builder()
+            .timeout(Duration::from_secs_f32(http.timeout_seconds))
+            .build()?;
+
+        Ok(Self {
+            client,
+            url,
+            model: http.model.clone(),
+            language: config.language.clone(),
+            temperature: config.temperature,
+            no_speech_thold: config.no_speech_thold,
+            api_key: http.api_key.clone(),
+            postprocess: PostProcessor::from_config(config)?,
+        })
+    }
+
+    /// 音声区間をサーバーに送信して認識結果を受け取る
+    fn request(&self, audio: &[f32], prompt: Option<&str>) -> Result<TranscriptionResponse> {
+        let file = Part::bytes(wav::encode_wav_i16(audio, SAMPLE_RATE)?)
+            .file_name("audio.wav")
+            .mime_str("audio/wav")?;
+        let mut form = Form::new()
+            .part("file", file)
+            .text("model", self.model.clone())
+            .text("response_format", "verbose_json")
+            .text("temperature", self.temperature.to_string());
+        if self.language != "auto" {
+            form = form.text("language", self.language.clone());
+        }
+        if let Some(prompt) = prompt {
+            form = form.text("prompt", prompt.to_string());
+        }
+
+        let mut builder = self.client.post(&self.url).multipart(form);
+        if let Some(api_key) = &self.api_key {
+            builder = builder.bearer_auth(api_key);
+        }
+        let response = builder
+            .send()
+            .map_err(|e| HttpSttError::ConnectionError(e.to_string()))?;
+
+        if !response.status().is_success() {
+            return Err(HttpSttError::TranscriptionError(format!(
+                "ステータスコード: {}",
+                response.status()
+            ))
+            .into());
+        }
+
+        let result: TranscriptionResponse = response
+            .json()
+            .map_err(|e| HttpSttError::TranscriptionError(e.to_string()))?;
+        Ok(result)
+    }
+}
+
+impl SpeechToText for HttpStt {
+    fn name(&self) -> &'static str {
+        "HTTP"
+    }
+
+    fn transcribe(&self, audio: &[f32]) -> Result<Transcript> {
+        let mut transcript = self.transcribe_with_prompt(audio, self.postprocess.prompt())?;
+        self.postprocess.apply(&mut transcript);
+        Ok(transcript)
+    }
+
+    fn transcribe_with_prompt(&self, audio: &[f32], prompt: Option<&str>) -> Result<Transcript> {
+        let start = Instant::now();
+        debug!("音声認識開始 (HTTP): {} サンプル ({:.2}秒)", audio.len(), audio.len() as f32 / SAMPLE_RATE as f32);
+
+        let vad_audio = WhisperStt::apply_vad(audio);
+        if vad_audio.is_empty() {
+            debug!("VAD: 音声区間が検出されませんでした");
+            return Ok(Transcript {
+                processing_time: start.elapsed(),
+                ..Transcript::default()
+            });
+        }
+        let response = self.request(&WhisperStt::normalize_audio(&vad_audio), prompt)?;
+
+        // セグメントがあればローカルと同じく無音確率の高いものを除く
+        let mut text = String::new();
+        let mut segments = Vec::new();
+        let mut probs = Vec::new();
+        for segment in response.segments {
+            let no_speech_prob = segment.no_speech_prob.unwrap_or(0.0);
+            let skipped = no_speech_prob > self.no_speech_thold;
+            if !skipped {
+                text.push_str(&segment.text);
+                // 平均対数確率からの近似（トークン確率の幾何平均）
+                probs.extend(segment.avg_logprob.map(f32::exp));
+            }
+            segments.push(TranscriptSegment {
+                text: segment.text,
+                start: segment.start,
+                end: segment.end,
+                no_speech_prob,
+                skipped,
+                tokens: Vec::new(),
+            });
+        }
+        if segments.is_empty() {
+            text = response.text;
+        }
+
+        let transcript = Transcript {
+            text: text.trim().to_string(),
+            raw_text: text.trim().to_string(),
+            segments,
+            language: response.language.map(|language| normalize_language(&language)),
+            avg_token_prob: (!probs.is_empty()).then(|| probs.iter().sum::<f32>() / probs.len() as f32),
+            speech_seconds: vad_audio.len() as f32 / SAMPLE_RATE as f32,
+            processing_time: start.elapsed(),
+            rejection: None,
+        };
+        debug!(
+            "音声認識完了 (HTTP): \"{}\" (language={:?}, avg_token_prob={:?}, {:.0}ms)",
+            transcript.text,
+            transcript.language,
+            transcript.avg_token_prob,
+            transcript.processing_time.as_secs_f32() * 1000.0
+        );
+        Ok(transcript)
+    }
+}
+
+/// 言語名（"japanese"など）をWhisperの言語コード（"ja"）に揃える
+fn normalize_language(language: &str) -> String {
+    let language = language.trim().to_lowercase();
+    whisper_rs::get_lang_id(&language)
+        .and_then(whisper_rs::get_lang_str)
+        .map(str::to_string)
+        .unwrap_or(language)
+}
@@ -1,3 +1,13 @@
+mod backend;
+mod hallucination;
+mod http;
+mod postprocess;
+mod streaming;
+mod transcript;
+mod vocabulary;
 mod whisper;
 
+pub use backend::{create_backend, SpeechToText};
+pub use streaming::StreamingTranscriber;
+pub use transcript::Transcript;
 pub use whisper::WhisperStt;
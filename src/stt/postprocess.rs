@@ -0,0 +1,81 @@
+use anyhow::Result;
+use log::debug;
+
+use super::hallucination::HallucinationFilter;
+use super::transcript::Transcript;
+use super::vocabulary::Vocabulary;
+use crate::config::{NormalizeConfig, NumeralStyle, SttConfig};
+use crate::text::normalize;
+
+/// 認識結果の後処理（バックエンド共通）
+pub struct PostProcessor {
+    /// 初期プロンプトと認識結果の語彙補正
+    vocabulary: Option<Vocabulary>,
+    /// ハルシネーションの除去
+    hallucination_filter: Option<HallucinationFilter>,
+    /// 日本語正規化（無効ならNone）
+    normalize: Option<NormalizeConfig>,
+}
+
+impl PostProcessor {
+    /// 設定から後処理を生成
+    pub fn from_config(config: &SttConfig) -> Result<Self> {
+        Ok(Self {
+            vocabulary: Vocabulary::from_config(config)?,
+            hallucination_filter: HallucinationFilter::from_config(&config.hallucination),
+            normalize: config.normalize.enabled.then(|| config.normalize.clone()),
+        })
+    }
+
+    /// 認識時に与える初期プロンプト（語彙の誘導用）
+    pub fn prompt(&self) -> Option<&str> {
+        self.vocabulary.as_ref().and_then(Vocabulary::prompt)
+    }
+
+    /// ハルシネーションを除去し、残った認識結果をフレーズに補正して正規化する
+    ///
+    /// 正規化は最後に行い、語彙補正で置き換えたフレーズも同じ表記に揃える。
+    /// 後処理前のテキストは`raw_text`に残る。
+    pub fn apply(&self, transcript: &mut Transcript) {
+        if let Some(filter) = &self.hallucination_filter {
+            filter.apply(transcript);
+        }
+        if transcript.is_empty() {
+            return;
+        }
+        if let Some(vocabulary) = &self.vocabulary {
+            transcript.text = vocabulary.correct(&transcript.text).0;
+        }
+        let is_japanese = !matches!(transcript.language.as_deref(), Some(language) if language != "ja");
+        if let Some(config) = self.normalize.as_ref().filter(|_| is_japanese) {
+            let normalized = normalize_text(&transcript.text, config);
+            if normalized != transcript.text {
+                debug!("正規化: \"{}\" -> \"{}\"", transcript.text, normalized);
+            }
+            transcript.text = normalized;
+        }
+    }
+}
+
+/// 設定に従って日本語のテキストを正規化
+///
+/// 全角・半角の統一 → フィラー除去 → 数字の表記 → 句読点の整理の順に行う。
+fn normalize_text(text: &str, config: &NormalizeConfig) -> String {
+    let mut text = if config.width {
+        normalize::fold_width(text)
+    } else {
+        text.to_string()
+    };
+    if !config.fillers.is_empty() {
+        text = normalize::remove_fillers(&text, &config.fillers);
+    }
+    text = match config.numerals {
+        NumeralStyle::Digits => normalize::to_digit_numerals(&text),
+        NumeralStyle::Kanji => normalize::to_kanji_numerals(&text),
+        NumeralStyle::Keep => text,
+    };
+    if config.punctuation {
+        text = normalize::clean_punctuation(&text);
+    }
+    text.trim().to_string()
+}
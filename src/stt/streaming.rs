@@ -0,0 +1,137 @@
+use anyhow::Result;
+use log::{debug, info, warn};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use super::{SpeechToText, Transcript};
+use crate::audio::RecordingMonitor;
+use crate::config::StreamingConfig;
+
+/// 入力サンプルレート
+const SAMPLE_RATE: f32 = 16000.0;
+
+/// 途中認識の結果
+struct Partial {
+    /// 認識結果
+    transcript: Transcript,
+    /// 認識した区間の開始位置（サンプル数）
+    start: usize,
+    /// 認識した区間の終了位置（サンプル数）
+    end: usize,
+}
+
+/// 録音と並行して途中認識を行うストリーミング認識
+///
+/// 録音中のバッファを一定間隔で読み出して認識し、途中結果を通知する。
+/// 発話終了（無音検出）の時点までを認識済みであれば、録音終了後に
+/// 再認識せずその結果を最終結果として返す。
+pub struct StreamingTranscriber<'a> {
+    stt: &'a dyn SpeechToText,
+    interval: Duration,
+    window_samples: usize,
+    min_new_samples: usize,
+}
+
+impl<'a> StreamingTranscriber<'a> {
+    pub fn new(stt: &'a dyn SpeechToText, config: &StreamingConfig) -> Self {
+        Self {
+            stt,
+            interval: Duration::from_secs_f32(config.interval_seconds.max(0.05)),
+            window_samples: (config.window_seconds * SAMPLE_RATE) as usize,
+            min_new_samples: (config.interval_seconds * SAMPLE_RATE) as usize,
+        }
+    }
+
+    /// 録音しながら途中認識を行い、録音した音声と最終結果を返す
+    ///
+    /// # Arguments
+    /// * `monitor` - 録音中の音声を参照するモニター
+    /// * `record` - 録音を実行する処理（呼び出し元のスレッドで実行）
+    /// * `on_partial` - 途中結果の通知先（認識スレッドから呼ばれる）
+    pub fn run(
+        &self,
+        monitor: &RecordingMonitor,
+        record: impl FnOnce() -> Result<Vec<f32>>,
+        on_partial: &(dyn Fn(&str) + Sync),
+    ) -> Result<(Vec<f32>, Transcript)> {
+        let done = AtomicBool::new(false);
+        let latest: Mutex<Option<Partial>> = Mutex::new(None);
+
+        let audio = std::thread::scope(|scope| {
+            scope.spawn(|| self.decode_loop(monitor, &done, &latest, on_partial));
+            let recorded = record();
+            done.store(true, Ordering::Relaxed);
+            recorded
+        })?;
+        if audio.is_empty() {
+            return Ok((audio, Transcript::default()));
+        }
+        let finalize_start = Instant::now();
+
+        // 発話終了位置（末尾の無音を除く）まで認識済みなら、その結果を使う
+        let speech_end = audio.len().saturating_sub(monitor.trailing_silence());
+        let partial = latest.into_inner().unwrap();
+        if let Some(partial) = partial.filter(|p| p.start == 0 && p.end >= speech_end && p.end <= audio.len()) {
+            info!(
+                "ストリーミング認識: 途中結果を最終結果として使用 ({:.2}秒/{:.2}秒認識済み)",
+                partial.end as f32 / SAMPLE_RATE,
+                audio.len() as f32 / SAMPLE_RATE
+            );
+            return Ok((audio, partial.transcript));
+        }
+
+        debug!("ストリーミング認識: 発話末尾が未認識のため全体を再認識");
+        let transcript = self.stt.transcribe(&audio)?;
+        info!(
+            "ストリーミング認識: 録音終了から{:.2}秒で確定",
+            finalize_start.elapsed().as_secs_f32()
+        );
+        Ok((audio, transcript))
+    }
+
+    /// 録音が終わるまで一定間隔で途中認識を繰り返す（認識スレッドで実行）
+    fn decode_loop(
+        &self,
+        monitor: &RecordingMonitor,
+        done: &AtomicBool,
+        latest: &Mutex<Option<Partial>>,
+        on_partial: &(dyn Fn(&str) + Sync),
+    ) {
+        let mut decoded_until = 0usize;
+        while !done.load(Ordering::Relaxed) {
+            std::thread::sleep(self.interval);
+
+            let Some(snapshot) = monitor.snapshot() else {
+                continue;
+            };
+            let end = snapshot.audio.len();
+            if !snapshot.speech_detected || end < decoded_until + self.min_new_samples {
+                continue;
+            }
+
+            // 長い発話は直近window_seconds秒のみを認識する
+            let start = end.saturating_sub(self.window_samples);
+            let transcript = match self.stt.transcribe(&snapshot.audio[start..end]) {
+                Ok(transcript) => transcript,
+                Err(e) => {
+                    warn!("途中認識に失敗: {:#}", e);
+                    continue;
+                }
+            };
+            debug!(
+                "途中認識: {:.2}〜{:.2}秒 \"{}\" ({:.0}ms)",
+                start as f32 / SAMPLE_RATE,
+                end as f32 / SAMPLE_RATE,
+                transcript.text,
+                transcript.processing_time.as_secs_f32() * 1000.0
+            );
+
+            decoded_until = end;
+            if !transcript.is_empty() {
+                on_partial(&transcript.text);
+            }
+            *latest.lock().unwrap() = Some(Partial { transcript, start, end });
+        }
+    }
+}
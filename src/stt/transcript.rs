@@ -0,0 +1,69 @@
+use serde::Serialize;
+use std::time::Duration;
+
+use super::hallucination::Rejection;
+
+/// 認識結果のトークン
+#[derive(Debug, Clone, Serialize)]
+pub struct TranscriptToken {
+    pub text: String,
+    /// 開始時刻（秒、無音区間除去後の音声上の位置）
+    pub start: f32,
+    /// 終了時刻（秒、無音区間除去後の音声上の位置）
+    pub end: f32,
+    /// トークンの確率（0.0〜1.0）
+    pub probability: f32,
+}
+
+/// 認識結果のセグメント
+#[derive(Debug, Clone, Serialize)]
+pub struct TranscriptSegment {
+    pub text: String,
+    /// 開始時刻（秒、無音区間除去後の音声上の位置）
+    pub start: f32,
+    /// 終了時刻（秒、無音区間除去後の音声上の位置）
+    pub end: f32,
+    /// 無音確率（0.0〜1.0）
+    pub no_speech_prob: f32,
+    /// 無音確率が高いためテキストに含めなかったか
+    pub skipped: bool,
+    /// テキストトークン（特殊トークンは除く）
+    pub tokens: Vec<TranscriptToken>,
+}
+
+/// 音声認識の結果
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Transcript {
+    /// 認識結果のテキスト（ハルシネーション除去・語彙補正・正規化後）
+    pub text: String,
+    /// Whisperが出力したままのテキスト（後処理前）
+    pub raw_text: String,
+    /// セグメント（Whisperの出力そのまま）
+    pub segments: Vec<TranscriptSegment>,
+    /// 認識言語（自動検出時は検出結果）
+    pub language: Option<String>,
+    /// 採用したセグメントのトークンの平均確率
+    pub avg_token_prob: Option<f32>,
+    /// 無音区間除去後の音声の長さ（秒）
+    pub speech_seconds: f32,
+    /// 認識処理にかかった時間
+    pub processing_time: Duration,
+    /// ハルシネーションとして棄却した理由（棄却時はtextが空）
+    pub rejection: Option<Rejection>,
+}
+
+impl Transcript {
+    /// テキストが空か（認識できなかった、または棄却した）
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    /// 採用したセグメントの最大の無音確率
+    pub fn max_no_speech_prob(&self) -> Option<f32> {
+        self.segments
+            .iter()
+            .filter(|segment| !segment.skipped)
+            .map(|segment| segment.no_speech_prob)
+            .reduce(f32::max)
+    }
+}
@@ -0,0 +1,196 @@
+use anyhow::{Context, Result};
+use log::{debug, info};
+use std::fs;
+
+use crate::config::SttConfig;
+use crate::text::{fuzzy, kana};
+
+/// 語彙補正の1件
+#[derive(Debug, Clone)]
+pub struct Correction {
+    /// 認識結果の該当部分
+    pub from: String,
+    /// 置き換えたフレーズ
+    pub to: String,
+    /// 類似度（0.0〜1.0）
+    pub similarity: f32,
+}
+
+/// 登録フレーズ（正しい表記と照合用の読みキー）
+struct VocabularyPhrase {
+    text: String,
+    /// 表記と読みそれぞれの読みキー
+    keys: Vec<Vec<char>>,
+}
+
+/// 置き換え候補
+struct Candidate {
+    start: usize,
+    end: usize,
+    phrase: usize,
+    similarity: f32,
+}
+
+/// 固有名詞・デバイス名などの語彙によるWhisperの誘導と認識結果の補正
+///
+/// 登録フレーズを初期プロンプトとして与え、認識後は読みがあいまい一致した
+/// 部分を正しい表記に置き換える。
+pub struct Vocabulary {
+    phrases: Vec<VocabularyPhrase>,
+    prompt: Option<String>,
+    min_similarity: f32,
+}
+
+impl Vocabulary {
+    /// 設定から語彙を生成（initial_prompt・phrases_pathとも未指定ならNone）
+    ///
+    /// フレーズファイルは1行1フレーズ。`表記|よみ`の形式で読みを添えると、
+    /// かな・カタカナで認識された場合も補正できる。`#`以降はコメント。
+    pub fn from_config(config: &SttConfig) -> Result<Option<Self>> {
+        if config.initial_prompt.is_none() && config.phrases_path.is_none() {
+            return Ok(None);
+        }
+
+        let mut phrases = Vec::new();
+        if let Some(path) = &config.phrases_path {
+            let content = fs::read_to_string(path)
+                .with_context(|| format!("フレーズファイルの読み込みに失敗: {}", path))?;
+            phrases = parse_phrases(&content);
+            info!("フレーズファイル読み込み完了: {} ({} 件)", path, phrases.len());
+        }
+
+        let phrase_list = phrases.iter().map(|p| p.text.as_str()).collect::<Vec<_>>().join("、");
+        let prompt = match (&config.initial_prompt, phrase_list.is_empty()) {
+            (Some(prompt), true) => Some(prompt.clone()),
+            (Some(prompt), false) => Some(format!("{}\n{}", prompt, phrase_list)),
+            (None, false) => Some(phrase_list),
+            (None, true) => None,
+        };
+        debug!("Whisper初期プロンプト: {:?}", prompt);
+
+        Ok(Some(Self {
+            phrases,
+            prompt,
+            min_similarity: config.phrase_min_similarity,
+        }))
+    }
+
+    /// Whisperに与える初期プロンプト
+    pub fn prompt(&self) -> Option<&str> {
+        self.prompt.as_deref()
+    }
+
+    /// 登録フレーズに近い部分を正しい表記に置き換える
+    ///
+    /// # Returns
+    /// 補正後のテキストと補正内容
+    pub fn correct(&self, text: &str) -> (String, Vec<Correction>) {
+        let chars: Vec<char> = text.chars().collect();
+        let mut candidates = self.find_candidates(&chars);
+
+        // 類似度が高く長い候補から、重ならないものを採用
+        candidates.sort_by(|a, b| {
+            b.similarity
+                .total_cmp(&a.similarity)
+                .then((b.end - b.start).cmp(&(a.end - a.start)))
+        });
+        let mut taken = vec![false; chars.len()];
+        let mut accepted: Vec<&Candidate> = Vec::new();
+        for candidate in &candidates {
+            if taken[candidate.start..candidate.end].iter().any(|&t| t) {
+                continue;
+            }
+            taken[candidate.start..candidate.end].iter_mut().for_each(|t| *t = true);
+            accepted.push(candidate);
+        }
+        accepted.sort_by_key(|c| c.start);
+
+        let mut corrected = String::with_capacity(text.len());
+        let mut corrections = Vec::new();
+        let mut pos = 0;
+        for candidate in accepted {
+            corrected.extend(&chars[pos..candidate.start]);
+            let from: String = chars[candidate.start..candidate.end].iter().collect();
+            let to = &self.phrases[candidate.phrase].text;
+            if &from != to {
+                corrections.push(Correction {
+                    from,
+                    to: to.clone(),
+                    similarity: candidate.similarity,
+                });
+            }
+            corrected.push_str(to);
+            pos = candidate.end;
+        }
+        corrected.extend(&chars[pos..]);
+
+        for correction in &corrections {
+            debug!(
+                "語彙補正: \"{}\" -> \"{}\" (similarity={:.2})",
+                correction.from, correction.to, correction.similarity
+            );
+        }
+        (corrected, corrections)
+    }
+
+    /// 各フレーズについて、読みキーの長さ±1文字の区間で閾値以上に一致する候補を列挙
+    fn find_candidates(&self, chars: &[char]) -> Vec<Candidate> {
+        let mut candidates = Vec::new();
+        for (index, phrase) in self.phrases.iter().enumerate() {
+            let surface_len = phrase.text.chars().count();
+            let min_len = phrase.keys.iter().map(Vec::len).chain([surface_len]).min().unwrap_or(0);
+            let max_len = phrase.keys.iter().map(Vec::len).chain([surface_len]).max().unwrap_or(0);
+
+            for len in min_len.saturating_sub(1).max(1)..=max_len + 1 {
+                for start in 0..chars.len().saturating_sub(len - 1) {
+                    let window = &chars[start..start + len];
+                    // 区切り記号をまたいで置き換えないよう、記号で始まる・終わる区間は除く
+                    if !is_word_char(window[0]) || !is_word_char(window[len - 1]) {
+                        continue;
+                    }
+                    let window_text: String = window.iter().collect();
+                    let window_key: Vec<char> = kana::to_reading_key(&window_text).chars().collect();
+                    let similarity = phrase
+                        .keys
+                        .iter()
+                        .map(|key| fuzzy::char_similarity(&window_key, key))
+                        .fold(0.0_f32, f32::max);
+                    if similarity >= self.min_similarity {
+                        candidates.push(Candidate {
+                            start,
+                            end: start + len,
+                            phrase: index,
+                            similarity,
+                        });
+                    }
+                }
+            }
+        }
+        candidates
+    }
+}
+
+/// フレーズファイルをパース（`表記` または `表記|よみ`、`#`以降はコメント）
+fn parse_phrases(content: &str) -> Vec<VocabularyPhrase> {
+    content
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or("").trim())
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            let mut parts = line.split('|').map(str::trim);
+            let text = parts.next().unwrap_or("").to_string();
+            let keys = std::iter::once(text.as_str())
+                .chain(parts)
+                .map(|s| kana::to_reading_key(s).chars().collect::<Vec<char>>())
+                .filter(|key| !key.is_empty())
+                .collect();
+            VocabularyPhrase { text, keys }
+        })
+        .filter(|phrase| !phrase.text.is_empty())
+        .collect()
+}
+
+/// 読みキーに残る文字か（記号・空白以外）
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == 'ー'
+}
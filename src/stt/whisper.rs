@@ -1,9 +1,15 @@
 use anyhow::Result;
-use log::{debug, info};
+use log::{debug, info, warn};
+use std::ops::{Deref, DerefMut};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 use thiserror::Error;
-use whisper_rs::{FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters};
+use whisper_rs::{FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters, WhisperState};
 
-use crate::config::SttConfig;
+use super::backend::SpeechToText;
+use super::postprocess::PostProcessor;
+use super::transcript::{Transcript, TranscriptSegment, TranscriptToken};
+use crate::config::{DecodingStrategy, SttConfig};
 
 /// 音量正規化のターゲットピーク値（0.8〜0.95推奨）
 const NORMALIZATION_TARGET: f32 = 0.9;
@@ -20,6 +26,24 @@ const VAD_MARGIN_FRAMES: usize = 5;
 /// 音声区間間のギャップをマージする最大フレーム数
 const VAD_MAX_GAP_FRAMES: usize = 10;
 
+// cpu指定時に他のバックエンドのfeatureも有効だと、CPUのみのビルドにならない
+#[cfg(all(feature = "cpu", any(feature = "cuda", feature = "vulkan", feature = "openblas")))]
+compile_error!("feature \"cpu\" は cuda / vulkan / openblas と同時に指定できません");
+
+/// ビルド時に選択したGPUバックエンド（Cargoのfeature、CPUのみならNone）
+const GPU_BACKEND: Option<&str> = if cfg!(feature = "cuda") {
+    Some("CUDA")
+} else if cfg!(feature = "vulkan") {
+    Some("Vulkan")
+} else {
+    None
+};
+
+/// 再利用のために保持するWhisperStateの最大数（同時に認識する数）
+const MAX_POOLED_STATES: usize = 2;
+/// ウォームアップに使うダミー音声の長さ（whisper.cppは1秒未満の入力を処理しない）
+const WARMUP_SAMPLES: usize = 16000 * 2;
+
 /// STT処理に関するエラー
 #[derive(Debug, Error)]
 pub enum SttError {
@@ -30,10 +54,48 @@ pub enum SttError {
     TranscriptionError(String),
 }
 
+/// プールから取り出したWhisperState（ドロップ時にプールへ戻す）
+struct PooledState<'a> {
+    state: Option<WhisperState>,
+    pool: &'a Mutex<Vec<WhisperState>>,
+    /// 以前の認識で使ったStateか（falseなら今回作成）
+    reused: bool,
+}
+
+impl Deref for PooledState<'_> {
+    type Target = WhisperState;
+
+    fn deref(&self) -> &WhisperState {
+        self.state.as_ref().unwrap()
+    }
+}
+
+impl DerefMut for PooledState<'_> {
+    fn deref_mut(&mut self) -> &mut WhisperState {
+        self.state.as_mut().unwrap()
+    }
+}
+
+impl Drop for PooledState<'_> {
+    fn drop(&mut self) {
+        let Some(state) = self.state.take() else {
+            return;
+        };
+        let mut pool = self.pool.lock().unwrap();
+        if pool.len() < MAX_POOLED_STATES {
+            pool.push(state);
+        }
+    }
+}
+
 /// Whisperを使用した音声認識エンジン
 pub struct WhisperStt {
     ctx: WhisperContext,
-    language: String,
+    /// 再利用するWhisperState（作成にはバッファ確保などの時間がかかるため）
+    state_pool: Mutex<Vec<WhisperState>>,
+    config: SttConfig,
+    /// ハルシネーション除去・語彙補正
+    postprocess: PostProcessor,
 }
 
 impl WhisperStt {
@@ -46,53 +108,166 @@ impl WhisperStt {
     /// 初期化されたWhisperSttインスタンス
     pub fn new(config: &SttConfig) -> Result<Self> {
         info!("Whisperモデルを読み込み中: {}", config.model_path);
+        debug!("whisper.cpp: {}", whisper_rs::print_system_info());
 
-        let ctx = WhisperContext::new_with_params(&config.model_path, WhisperContextParameters::default())
-            .map_err(|e| SttError::ModelLoadError(format!("{}: {}", config.model_path, e)))?;
-
-        info!("Whisperモデルの読み込み完了");
+        let use_gpu = match GPU_BACKEND {
+            Some(_) => config.use_gpu,
+            None => {
+                if config.use_gpu {
+                    warn!("GPU対応（cuda / vulkan feature）なしでビルドされているため、CPUで推論します");
+                }
+                false
+            }
+        };
+        // GPUで読み込めなければ（ドライバ・デバイスなし、メモリ不足など）CPUで再試行する
+        let (ctx, use_gpu) = match Self::load_context(config, use_gpu) {
+            Ok(ctx) => (ctx, use_gpu),
+            Err(e) if use_gpu => {
+                warn!("GPUでのモデル読み込みに失敗したため、CPUで再試行します: {}", e);
+                (Self::load_context(config, false)?, false)
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        info!(
+            "Whisperモデルの読み込み完了 (backend={}, flash_attn={}, strategy={:?}, beam_size={}, temperature={}+{}, n_threads={:?})",
+            match GPU_BACKEND {
+                Some(backend) if use_gpu => format!("{} (gpu_device={})", backend, config.gpu_device),
+                _ => "CPU".to_string(),
+            },
+            config.flash_attn,
+            config.strategy,
+            config.beam_size,
+            config.temperature,
+            config.temperature_inc,
+            config.n_threads
+        );
 
-        Ok(Self {
+        let stt = Self {
             ctx,
-            language: config.language.clone(),
+            state_pool: Mutex::new(Vec::new()),
+            config: config.clone(),
+            postprocess: PostProcessor::from_config(config)?,
+        };
+        if config.warmup {
+            stt.warm_up()?;
+        }
+        Ok(stt)
+    }
+
+    /// モデルを読み込む
+    fn load_context(config: &SttConfig, use_gpu: bool) -> Result<WhisperContext, SttError> {
+        let mut ctx_params = WhisperContextParameters::default();
+        ctx_params.use_gpu(use_gpu);
+        ctx_params.gpu_device(config.gpu_device);
+        ctx_params.flash_attn(config.flash_attn);
+
+        WhisperContext::new_with_params(&config.model_path, ctx_params)
+            .map_err(|e| SttError::ModelLoadError(format!("{}: {}", config.model_path, e)))
+    }
+
+    /// ダミー音声で推論してモデルを温める
+    ///
+    /// 初回の推論はStateの作成やGPUカーネルの初期化を含み遅いため、起動時に済ませておく。
+    /// 1回目（コールド）と2回目（ウォーム）の時間を記録する。
+    fn warm_up(&self) -> Result<()> {
+        info!("Whisperウォームアップ中...");
+        let audio = vec![0.0_f32; WARMUP_SAMPLES];
+        let cold = self.run_dummy(&audio)?;
+        let warm = self.run_dummy(&audio)?;
+        info!(
+            "Whisperウォームアップ完了: コールド {:.0}ms, ウォーム {:.0}ms",
+            cold.as_secs_f32() * 1000.0,
+            warm.as_secs_f32() * 1000.0
+        );
+        Ok(())
+    }
+
+    /// ダミー音声を推論し、かかった時間を返す
+    fn run_dummy(&self, audio: &[f32]) -> Result<Duration> {
+        let start = Instant::now();
+        let mut state = self.acquire_state()?;
+        state
+            .full(self.full_params(None, None), audio)
+            .map_err(|e| SttError::TranscriptionError(format!("ウォームアップに失敗: {}", e)))?;
+        Ok(start.elapsed())
+    }
+
+    /// プールからWhisperStateを取り出す（空なら新しく作成）
+    fn acquire_state(&self) -> Result<PooledState<'_>> {
+        let pooled = self.state_pool.lock().unwrap().pop();
+        let reused = pooled.is_some();
+        let state = match pooled {
+            Some(state) => state,
+            None => {
+                let start = Instant::now();
+                let state = self
+                    .ctx
+                    .create_state()
+                    .map_err(|e| SttError::TranscriptionError(format!("状態の作成に失敗: {}", e)))?;
+                debug!("WhisperStateを作成 ({:.0}ms)", start.elapsed().as_secs_f32() * 1000.0);
+                state
+            }
+        };
+        Ok(PooledState {
+            state: Some(state),
+            pool: &self.state_pool,
+            reused,
         })
     }
 
-    /// 音声データをテキストに変換
+    /// 言語・temperatureをリクエストごとに指定して音声データを認識（後処理なし、serve-stt用）
     ///
     /// # Arguments
-    /// * `audio` - 音声データ（f32, 16kHz, モノラル, -1.0〜1.0の範囲）
+    /// * `audio` - 16kHzの音声データ
+    /// * `prompt` - 初期プロンプト
+    /// * `language` - 認識言語（"auto"で自動判定）
+    /// * `temperature` - 初期temperature（Noneなら設定値）
+    pub fn transcribe_request(
+        &self,
+        audio: &[f32],
+        prompt: Option<&str>,
+        language: &str,
+        temperature: Option<f32>,
+    ) -> Result<Transcript> {
+        self.recognize(audio, prompt, Some(language), temperature)
+    }
+
+    /// 初期プロンプトを指定して音声データを認識（後処理なし）
+    ///
+    /// 言語・temperatureがNoneなら設定値を使う。
     ///
     /// # Returns
-    /// 認識されたテキスト
-    pub fn transcribe(&self, audio: &[f32]) -> Result<String> {
+    /// 認識結果（テキスト・セグメント・トークンの時刻と確率・言語・処理時間）
+    fn recognize(
+        &self,
+        audio: &[f32],
+        prompt: Option<&str>,
+        language: Option<&str>,
+        temperature: Option<f32>,
+    ) -> Result<Transcript> {
+        let start = Instant::now();
         debug!("音声認識開始: {} サンプル ({:.2}秒)", audio.len(), audio.len() as f32 / 16000.0);
 
         // 前処理1: VAD（無音区間除去）
         let vad_audio = Self::apply_vad(audio);
         if vad_audio.is_empty() {
             debug!("VAD: 音声区間が検出されませんでした");
-            return Ok(String::new());
+            return Ok(Transcript {
+                processing_time: start.elapsed(),
+                ..Transcript::default()
+            });
         }
 
         // 前処理2: 音量正規化（精度改善の最重要項目）
         let normalized_audio = Self::normalize_audio(&vad_audio);
 
-        // BeamSearch使用（精度向上、速度はやや低下）
-        let mut params = FullParams::new(SamplingStrategy::BeamSearch {
-            beam_size: 5,
-            patience: 1.0,
-        });
-        params.set_language(Some(&self.language));
-        // temperature = 0 でランダム性を排除し安定化
-        params.set_temperature(0.0);
-        params.set_print_special(false);
-        params.set_print_progress(false);
-        params.set_print_realtime(false);
-        params.set_print_timestamps(false);
+        let mut params = self.full_params(language, temperature);
+        if let Some(prompt) = prompt {
+            params.set_initial_prompt(prompt);
+        }
 
-        let mut state = self.ctx.create_state()
-            .map_err(|e| SttError::TranscriptionError(format!("状態の作成に失敗: {}", e)))?;
+        let mut state = self.acquire_state()?;
 
         state.full(params, &normalized_audio)
             .map_err(|e| SttError::TranscriptionError(format!("認識処理に失敗: {}", e)))?;
@@ -100,33 +275,125 @@ impl WhisperStt {
         let num_segments = state.full_n_segments();
 
         let mut result = String::new();
+        let mut segments = Vec::new();
+        let mut prob_sum = 0.0_f32;
+        let mut token_count = 0usize;
         for i in 0..num_segments {
-            if let Some(segment) = state.get_segment(i) {
-                // 無音確率が高いセグメントはスキップ（ハルシネーション防止）
-                let no_speech_prob = segment.no_speech_probability();
-                if no_speech_prob > 0.6 {
-                    debug!("セグメント{}: 無音確率が高いためスキップ (no_speech_prob={:.2})", i, no_speech_prob);
+            let Some(segment) = state.get_segment(i) else {
+                continue;
+            };
+            let text = segment.to_str_lossy().map(|t| t.into_owned()).unwrap_or_default();
+
+            // 無音確率が高いセグメントはスキップ（ハルシネーション防止）
+            let no_speech_prob = segment.no_speech_probability();
+            let skipped = no_speech_prob > self.config.no_speech_thold;
+            if skipped {
+                debug!("セグメント{}: 無音確率が高いためスキップ (no_speech_prob={:.2})", i, no_speech_prob);
+            } else {
+                debug!("セグメント{}: \"{}\" (no_speech_prob={:.2})", i, text, no_speech_prob);
+                result.push_str(&text);
+            }
+
+            // テキストトークン（特殊トークンは除く）
+            let mut tokens = Vec::new();
+            for j in 0..segment.n_tokens() {
+                let Some(token) = segment.get_token(j) else {
+                    continue;
+                };
+                let Ok(token_text) = token.to_str_lossy() else {
+                    continue;
+                };
+                if is_special_token(&token_text) {
                     continue;
                 }
-
-                if let Ok(text) = segment.to_str_lossy() {
-                    debug!("セグメント{}: \"{}\" (no_speech_prob={:.2})", i, text, no_speech_prob);
-                    result.push_str(&text);
+                let data = token.token_data();
+                let probability = token.token_probability();
+                if !skipped {
+                    prob_sum += probability;
+                    token_count += 1;
                 }
+                tokens.push(TranscriptToken {
+                    text: token_text.into_owned(),
+                    start: centis_to_secs(data.t0),
+                    end: centis_to_secs(data.t1),
+                    probability,
+                });
             }
+
+            segments.push(TranscriptSegment {
+                text,
+                start: centis_to_secs(segment.start_timestamp()),
+                end: centis_to_secs(segment.end_timestamp()),
+                no_speech_prob,
+                skipped,
+                tokens,
+            });
         }
 
-        let result = result.trim().to_string();
-        debug!("音声認識完了: \"{}\"", result);
+        let transcript = Transcript {
+            text: result.trim().to_string(),
+            raw_text: result.trim().to_string(),
+            segments,
+            language: detected_language(state.full_lang_id_from_state()),
+            avg_token_prob: (token_count > 0).then(|| prob_sum / token_count as f32),
+            speech_seconds: vad_audio.len() as f32 / 16000.0,
+            processing_time: start.elapsed(),
+            rejection: None,
+        };
+        debug!(
+            "音声認識完了: \"{}\" (language={:?}, avg_token_prob={:?}, {:.0}ms, state={})",
+            transcript.text,
+            transcript.language,
+            transcript.avg_token_prob,
+            transcript.processing_time.as_secs_f32() * 1000.0,
+            if state.reused { "再利用" } else { "新規" }
+        );
 
-        Ok(result)
+        Ok(transcript)
+    }
+
+    /// 設定からデコードパラメータを生成（言語・temperatureは指定があれば上書き）
+    fn full_params<'a>(&'a self, language: Option<&'a str>, temperature: Option<f32>) -> FullParams<'a, 'a> {
+        let config = &self.config;
+        let strategy = match config.strategy {
+            // ビームサーチ（精度向上、速度はやや低下）
+            DecodingStrategy::Beam => SamplingStrategy::BeamSearch {
+                beam_size: config.beam_size,
+                patience: config.patience,
+            },
+            DecodingStrategy::Greedy => SamplingStrategy::Greedy {
+                best_of: config.best_of,
+            },
+        };
+
+        let mut params = FullParams::new(strategy);
+        params.set_language(Some(language.unwrap_or(&config.language)));
+        // temperature = 0 でランダム性を排除し安定化（失敗時はtemperature_incずつ上げて再試行）
+        params.set_temperature(temperature.unwrap_or(config.temperature));
+        params.set_temperature_inc(config.temperature_inc);
+        params.set_entropy_thold(config.entropy_thold);
+        params.set_logprob_thold(config.logprob_thold);
+        params.set_no_speech_thold(config.no_speech_thold);
+        params.set_single_segment(config.single_segment);
+        if let Some(n_threads) = config.n_threads {
+            params.set_n_threads(n_threads);
+        }
+        params.set_print_special(false);
+        params.set_print_progress(false);
+        params.set_print_realtime(false);
+        params.set_print_timestamps(false);
+        // Stateを再利用するため、前回の認識結果を文脈として引き継がない
+        params.set_no_context(true);
+        // トークン単位の時刻を取得する
+        params.set_token_timestamps(true);
+        params
     }
 
     /// 音声データの音量正規化
     ///
     /// ピーク振幅を0.9に正規化することで認識精度を向上させる。
     /// Whisperは入力振幅不足に非常に敏感なため、これは最重要の前処理。
-    fn normalize_audio(audio: &[f32]) -> Vec<f32> {
+    pub(super) fn normalize_audio(audio: &[f32]) -> Vec<f32> {
         if audio.is_empty() {
             return Vec::new();
         }
@@ -167,7 +434,7 @@ impl WhisperStt {
     ///
     /// 音声区間のみを抽出することで、Whisperの誤認識を防ぐ。
     /// エネルギーベースのシンプルなVADを使用。
-    fn apply_vad(audio: &[f32]) -> Vec<f32> {
+    pub(super) fn apply_vad(audio: &[f32]) -> Vec<f32> {
         if audio.is_empty() {
             return Vec::new();
         }
@@ -269,3 +536,55 @@ impl WhisperStt {
         result
     }
 }
+
+impl SpeechToText for WhisperStt {
+    fn name(&self) -> &'static str {
+        "Whisper"
+    }
+
+    /// 語彙が設定されていれば初期プロンプトとして与え、認識後にハルシネーション除去・語彙補正を行う
+    fn transcribe(&self, audio: &[f32]) -> Result<Transcript> {
+        let mut transcript = self.recognize(audio, self.postprocess.prompt(), None, None)?;
+        self.postprocess.apply(&mut transcript);
+        Ok(transcript)
+    }
+
+    fn transcribe_with_prompt(&self, audio: &[f32], prompt: Option<&str>) -> Result<Transcript> {
+        self.recognize(audio, prompt, None, None)
+    }
+}
+
+/// 特殊トークン（[_BEG_]、<|ja|>など）か
+fn is_special_token(text: &str) -> bool {
+    let text = text.trim();
+    text.starts_with("[_") || text.starts_with("<|")
+}
+
+/// Whisperの時刻（10ms単位）を秒に変換
+fn centis_to_secs(centis: i64) -> f32 {
+    centis as f32 / 100.0
+}
+
+/// 認識時に検出した言語IDを言語コード（"ja"、"en"など）に変換（不明なIDはNone）
+fn detected_language(lang_id: i32) -> Option<String> {
+    whisper_rs::get_lang_str(lang_id).map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detected_language_maps_whisper_lang_ids() {
+        for code in ["ja", "en", "zh"] {
+            let lang_id = whisper_rs::get_lang_id(code).unwrap();
+            assert_eq!(detected_language(lang_id).as_deref(), Some(code));
+        }
+    }
+
+    #[test]
+    fn detected_language_is_none_for_unknown_ids() {
+        assert_eq!(detected_language(-1), None);
+        assert_eq!(detected_language(10_000), None);
+    }
+}
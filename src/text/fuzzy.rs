@@ -0,0 +1,66 @@
+/// 文字単位のレーベンシュタイン距離
+pub fn levenshtein(a: &[char], b: &[char]) -> usize {
+    if a.is_empty() {
+        return b.len();
+    }
+    if b.is_empty() {
+        return a.len();
+    }
+
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut curr = vec![0; b.len() + 1];
+
+    for (i, &ca) in a.iter().enumerate() {
+        curr[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let cost = if ca == cb { 0 } else { 1 };
+            curr[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(curr[j] + 1);
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+
+    prev[b.len()]
+}
+
+/// 文字配列同士の類似度（0.0〜1.0）
+pub fn char_similarity(a: &[char], b: &[char]) -> f32 {
+    let max_len = a.len().max(b.len());
+    if max_len == 0 {
+        return 1.0;
+    }
+    1.0 - levenshtein(a, b) as f32 / max_len as f32
+}
+
+/// 部分一致の結果
+#[derive(Debug, Clone, Copy)]
+pub struct WindowMatch {
+    /// 類似度（0.0〜1.0）
+    pub similarity: f32,
+}
+
+/// haystack中でneedleに最も近い部分文字列を探す
+///
+/// needleの長さ±1文字の窓をスライドさせ、最も高い類似度を返す。
+/// haystackがneedleより短い場合は全体同士を比較する。
+pub fn best_window_match(haystack: &[char], needle: &[char]) -> WindowMatch {
+    if haystack.len() <= needle.len() || needle.is_empty() {
+        return WindowMatch {
+            similarity: char_similarity(haystack, needle),
+        };
+    }
+
+    let mut best = WindowMatch { similarity: 0.0 };
+    let min_len = needle.len().saturating_sub(1).max(1);
+    let max_len = (needle.len() + 1).min(haystack.len());
+
+    for len in min_len..=max_len {
+        for start in 0..=(haystack.len() - len) {
+            let score = char_similarity(&haystack[start..start + len], needle);
+            if score > best.similarity {
+                best = WindowMatch { similarity: score };
+            }
+        }
+    }
+
+    best
+}
@@ -0,0 +1,144 @@
+/// ローマ字→ひらがな変換表（長い綴りから優先的に照合する）
+const ROMAJI_TABLE: &[(&str, &str)] = &[
+    // 3文字
+    ("kya", "きゃ"), ("kyu", "きゅ"), ("kyo", "きょ"),
+    ("sha", "しゃ"), ("shi", "し"), ("shu", "しゅ"), ("she", "しぇ"), ("sho", "しょ"),
+    ("sya", "しゃ"), ("syu", "しゅ"), ("syo", "しょ"),
+    ("cha", "ちゃ"), ("chi", "ち"), ("chu", "ちゅ"), ("che", "ちぇ"), ("cho", "ちょ"),
+    ("tya", "ちゃ"), ("tyu", "ちゅ"), ("tyo", "ちょ"), ("tsu", "つ"),
+    ("nya", "にゃ"), ("nyu", "にゅ"), ("nyo", "にょ"),
+    ("hya", "ひゃ"), ("hyu", "ひゅ"), ("hyo", "ひょ"),
+    ("mya", "みゃ"), ("myu", "みゅ"), ("myo", "みょ"),
+    ("rya", "りゃ"), ("ryu", "りゅ"), ("ryo", "りょ"),
+    ("gya", "ぎゃ"), ("gyu", "ぎゅ"), ("gyo", "ぎょ"),
+    ("jya", "じゃ"), ("jyu", "じゅ"), ("jyo", "じょ"),
+    ("zya", "じゃ"), ("zyu", "じゅ"), ("zyo", "じょ"),
+    ("bya", "びゃ"), ("byu", "びゅ"), ("byo", "びょ"),
+    ("pya", "ぴゃ"), ("pyu", "ぴゅ"), ("pyo", "ぴょ"),
+    // 2文字
+    ("ka", "か"), ("ki", "き"), ("ku", "く"), ("ke", "け"), ("ko", "こ"),
+    ("sa", "さ"), ("si", "し"), ("su", "す"), ("se", "せ"), ("so", "そ"),
+    ("ta", "た"), ("ti", "ち"), ("tu", "つ"), ("te", "て"), ("to", "と"),
+    ("na", "な"), ("ni", "に"), ("nu", "ぬ"), ("ne", "ね"), ("no", "の"),
+    ("ha", "は"), ("hi", "ひ"), ("fu", "ふ"), ("hu", "ふ"), ("he", "へ"), ("ho", "ほ"),
+    ("fa", "ふぁ"), ("fi", "ふぃ"), ("fe", "ふぇ"), ("fo", "ふぉ"),
+    ("ma", "ま"), ("mi", "み"), ("mu", "む"), ("me", "め"), ("mo", "も"),
+    ("ya", "や"), ("yu", "ゆ"), ("yo", "よ"),
+    ("ra", "ら"), ("ri", "り"), ("ru", "る"), ("re", "れ"), ("ro", "ろ"),
+    ("la", "ら"), ("li", "り"), ("lu", "る"), ("le", "れ"), ("lo", "ろ"),
+    ("wa", "わ"), ("wo", "を"),
+    ("ga", "が"), ("gi", "ぎ"), ("gu", "ぐ"), ("ge", "げ"), ("go", "ご"),
+    ("za", "ざ"), ("ji", "じ"), ("zi", "じ"), ("zu", "ず"), ("ze", "ぜ"), ("zo", "ぞ"),
+    ("ja", "じゃ"), ("ju", "じゅ"), ("je", "じぇ"), ("jo", "じょ"),
+    ("da", "だ"), ("di", "ぢ"), ("du", "づ"), ("de", "で"), ("do", "ど"),
+    ("ba", "ば"), ("bi", "び"), ("bu", "ぶ"), ("be", "べ"), ("bo", "ぼ"),
+    ("pa", "ぱ"), ("pi", "ぴ"), ("pu", "ぷ"), ("pe", "ぺ"), ("po", "ぽ"),
+    ("va", "ゔぁ"), ("vi", "ゔぃ"), ("vu", "ゔ"), ("ve", "ゔぇ"), ("vo", "ゔぉ"),
+    // 1文字
+    ("a", "あ"), ("i", "い"), ("u", "う"), ("e", "え"), ("o", "お"),
+    ("-", "ー"),
+];
+
+/// カタカナをひらがなに変換（それ以外の文字はそのまま）
+pub fn katakana_to_hiragana(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            'ァ'..='ヶ' => char::from_u32(c as u32 - 0x60).unwrap_or(c),
+            _ => c,
+        })
+        .collect()
+}
+
+/// ローマ字（ASCII英字）をひらがなに変換
+///
+/// ヘボン式・訓令式の主要な綴りに対応する。促音（子音の重ね）と撥音（n）も扱う。
+/// 変換できない文字はそのまま残す。
+pub fn romaji_to_hiragana(text: &str) -> String {
+    let chars: Vec<char> = text.to_lowercase().chars().collect();
+    let mut result = String::with_capacity(text.len());
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+
+        // 撥音: "nn"、または n の後に母音・y以外が続く/末尾
+        // （ヘボン式の "konnichiwa" のように nn の後に母音が続く場合は1文字だけ消費）
+        if c == 'n' {
+            match next {
+                Some('n') => {
+                    let after = chars.get(i + 2).copied();
+                    let consumed = if after.is_some_and(|a| is_vowel(a) || a == 'y') { 1 } else { 2 };
+                    result.push('ん');
+                    i += consumed;
+                    continue;
+                }
+                Some(n) if !is_vowel(n) && n != 'y' => {
+                    result.push('ん');
+                    i += 1;
+                    continue;
+                }
+                None => {
+                    result.push('ん');
+                    i += 1;
+                    continue;
+                }
+                _ => {}
+            }
+        }
+
+        // 促音: 同じ子音の重ね（n以外）
+        if c.is_ascii_alphabetic() && !is_vowel(c) && c != 'n' && next == Some(c) {
+            result.push('っ');
+            i += 1;
+            continue;
+        }
+
+        // 長い綴りから順に照合
+        let mut matched = false;
+        for len in (1..=3).rev() {
+            if i + len > chars.len() {
+                continue;
+            }
+            let candidate: String = chars[i..i + len].iter().collect();
+            if let Some((_, kana)) = ROMAJI_TABLE.iter().find(|(romaji, _)| *romaji == candidate) {
+                result.push_str(kana);
+                i += len;
+                matched = true;
+                break;
+            }
+        }
+
+        if !matched {
+            result.push(c);
+            i += 1;
+        }
+    }
+
+    result
+}
+
+/// 照合用の読みキーに正規化
+///
+/// 全角英数字を半角に畳み、ローマ字とカタカナをひらがなに揃え、
+/// 空白・記号を取り除く。
+pub fn to_reading_key(text: &str) -> String {
+    let folded: String = text
+        .chars()
+        .map(|c| match c {
+            // 全角ASCII → 半角
+            '！'..='～' => char::from_u32(c as u32 - 0xFEE0).unwrap_or(c),
+            _ => c,
+        })
+        .collect();
+
+    let hiragana = katakana_to_hiragana(&romaji_to_hiragana(&folded));
+    hiragana
+        .chars()
+        .filter(|c| c.is_alphanumeric() || *c == 'ー')
+        .collect()
+}
+
+fn is_vowel(c: char) -> bool {
+    matches!(c, 'a' | 'i' | 'u' | 'e' | 'o')
+}
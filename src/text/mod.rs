@@ -0,0 +1,3 @@
+pub mod fuzzy;
+pub mod kana;
+pub mod normalize;
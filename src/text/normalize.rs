@@ -0,0 +1,356 @@
+/// 半角カタカナ（U+FF61〜U+FF9F、「｡」〜「ﾟ」）に対応する全角文字
+const FULLWIDTH_KANA: &str = "。「」、・ヲァィゥェォャュョッーアイウエオカキクケコサシスセソタチツテトナニヌネノハヒフヘホマミムメモヤユヨラリルレロワン゛゜";
+
+/// 漢数字（位取りの数字）
+const KANJI_DIGITS: &[(char, u64)] = &[
+    ('〇', 0), ('零', 0), ('一', 1), ('二', 2), ('三', 3), ('四', 4),
+    ('五', 5), ('六', 6), ('七', 7), ('八', 8), ('九', 9),
+];
+/// 0〜9の漢数字（数値→漢数字の変換用）
+const KANJI_DIGIT_CHARS: [char; 10] = ['〇', '一', '二', '三', '四', '五', '六', '七', '八', '九'];
+/// 漢数字の位（十・百・千）
+const KANJI_SMALL_UNITS: &[(char, u64)] = &[('十', 10), ('百', 100), ('千', 1_000)];
+/// 漢数字の位（万・億・兆）
+const KANJI_LARGE_UNITS: &[(char, u64)] = &[('万', 10_000), ('億', 100_000_000), ('兆', 1_000_000_000_000)];
+
+/// 漢数字を算用数字にする助数詞（数字の直後に続く場合のみ変換する）
+const COUNTERS: &[&str] = &[
+    "時間", "時", "分", "秒", "日", "週間", "週", "か月", "ヶ月", "カ月", "月", "年", "歳", "才",
+    "個", "回", "度", "人", "円", "階", "件", "本", "枚", "匹", "台", "杯", "つ",
+    "%", "％", "パーセント", "キロ", "メートル", "グラム", "リットル",
+];
+/// 助数詞が続いても数字として読まない語（「十分に」「一時停止」など）
+const NUMERAL_EXCEPTIONS: &[&str] = &["十分に", "十分だ", "十分な", "一時停止", "一時的", "一時保存", "一時中断", "三日月", "一人称"];
+
+/// 「十分」を「10分」と読む直後の語（これ以外は文末・区切りの場合だけ変換する）
+const TEN_MINUTES_FOLLOWERS: &[&str] = &["後", "前", "間", "タイマー"];
+
+/// 直後が区切りでなくてもフィラーとみなす長さ（「えーと」「あのー」など、短い「あの」は除く）
+const UNAMBIGUOUS_FILLER_CHARS: usize = 3;
+
+/// 表記ゆれを畳む
+///
+/// 全角英数字・記号を半角に、全角スペースを半角に、半角カタカナを全角にする
+/// （濁点・半濁点は直前の文字と結合する）。
+pub fn fold_width(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            // 全角ASCII → 半角
+            '！'..='～' => result.push(char::from_u32(c as u32 - 0xFEE0).unwrap_or(c)),
+            '\u{3000}' => result.push(' '),
+            '｡'..='ﾟ' => {
+                let base = FULLWIDTH_KANA.chars().nth((c as u32 - '｡' as u32) as usize).unwrap_or(c);
+                let combined = match chars.peek() {
+                    Some('ﾞ') => voiced_kana(base),
+                    Some('ﾟ') => semi_voiced_kana(base),
+                    _ => None,
+                };
+                match combined {
+                    Some(kana) => {
+                        result.push(kana);
+                        chars.next();
+                    }
+                    None => result.push(base),
+                }
+            }
+            _ => result.push(c),
+        }
+    }
+    result
+}
+
+/// フィラー（「えーと」「あの」など）を取り除く
+///
+/// 文頭・句読点・空白の直後にあり、直後も区切り（または長音）であるものだけを取り除く。
+/// 「あの店」のように語の一部になっている場合は残す。
+/// 3文字以上のフィラーは直後が区切りでなくても取り除く。直後の読点・空白も除く。
+pub fn remove_fillers(text: &str, fillers: &[String]) -> String {
+    let mut fillers: Vec<Vec<char>> = fillers
+        .iter()
+        .map(|filler| filler.chars().collect::<Vec<char>>())
+        .filter(|filler| !filler.is_empty())
+        .collect();
+    // 長いフィラーから照合（「えーと」より先に「えーっと」）
+    fillers.sort_by_key(|filler| std::cmp::Reverse(filler.len()));
+
+    let chars: Vec<char> = text.chars().collect();
+    let mut result = String::with_capacity(text.len());
+    let mut i = 0;
+    while i < chars.len() {
+        if i == 0 || is_boundary(chars[i - 1]) {
+            if let Some(filler) = fillers.iter().find(|filler| chars[i..].starts_with(filler)) {
+                let mut end = i + filler.len();
+                while end < chars.len() && matches!(chars[end], 'ー' | '〜' | '~') {
+                    end += 1;
+                }
+                if end == chars.len() || is_boundary(chars[end]) || filler.len() >= UNAMBIGUOUS_FILLER_CHARS {
+                    while end < chars.len() && (is_pause(chars[end]) || chars[end].is_whitespace()) {
+                        end += 1;
+                    }
+                    i = end;
+                    continue;
+                }
+            }
+        }
+        result.push(chars[i]);
+        i += 1;
+    }
+    result
+}
+
+/// 助数詞の前の漢数字を算用数字にする（「三時十五分」→「3時15分」）
+///
+/// 「九州」「一緒」などを変えないよう、助数詞が続く場合だけ変換する。
+/// 桁区切りのカンマ（「1,000円」）も取り除く。
+pub fn to_digit_numerals(text: &str) -> String {
+    let chars: Vec<char> = remove_digit_separators(text).chars().collect();
+    let mut result = String::with_capacity(text.len());
+    let mut i = 0;
+    while i < chars.len() {
+        let end = i + chars[i..].iter().take_while(|&&c| is_kanji_numeral(c)).count();
+        if end == i {
+            result.push(chars[i]);
+            i += 1;
+            continue;
+        }
+
+        let rest: String = chars[i..].iter().collect();
+        let after: String = chars[end..].iter().collect();
+        let value = if NUMERAL_EXCEPTIONS.iter().any(|word| rest.starts_with(word))
+            || !COUNTERS.iter().any(|counter| after.starts_with(counter))
+            || (rest.starts_with("十分") && !is_ten_minutes(&chars[end + 1..]))
+        {
+            None
+        } else {
+            parse_kanji_number(&chars[i..end])
+        };
+        match value {
+            Some(value) => result.push_str(&value.to_string()),
+            None => result.extend(&chars[i..end]),
+        }
+        i = end;
+    }
+    result
+}
+
+/// 算用数字を漢数字にする（「15分」→「十五分」）
+///
+/// 小数（「1.5」）と16桁を超える数はそのまま残す。
+pub fn to_kanji_numerals(text: &str) -> String {
+    let chars: Vec<char> = remove_digit_separators(text).chars().collect();
+    let mut result = String::with_capacity(text.len());
+    let mut i = 0;
+    while i < chars.len() {
+        let end = i + chars[i..].iter().take_while(|c| c.is_ascii_digit()).count();
+        if end == i {
+            result.push(chars[i]);
+            i += 1;
+            continue;
+        }
+
+        let is_decimal = (i >= 2 && chars[i - 1] == '.' && chars[i - 2].is_ascii_digit())
+            || (chars.get(end) == Some(&'.') && chars.get(end + 1).is_some_and(|c| c.is_ascii_digit()));
+        let digits: String = chars[i..end].iter().collect();
+        match digits.parse::<u64>() {
+            Ok(value) if !is_decimal && digits.len() <= 16 => result.push_str(&format_kanji_number(value)),
+            _ => result.push_str(&digits),
+        }
+        i = end;
+    }
+    result
+}
+
+/// 「十分」の後の文字列から、時間の「10分」と読めるか判定（「十分後」「十分タイマー」「十分。」）
+///
+/// 「十分です」「十分で」のように副詞・形容動詞とも読めるものは変換しない。
+fn is_ten_minutes(after: &[char]) -> bool {
+    match after.first() {
+        None => true,
+        Some(&c) if is_boundary(c) => true,
+        Some(_) => {
+            let after: String = after.iter().collect();
+            TEN_MINUTES_FOLLOWERS.iter().any(|word| after.starts_with(word))
+        }
+    }
+}
+
+/// 句読点・空白を整える
+///
+/// - 日本語の間の空白を除き、英数字の間の空白は1つにまとめる
+/// - 連続した同じ句読点、「、。」「。、」を1つにまとめる
+/// - 先頭の句読点と末尾の読点、音符記号を除く
+pub fn clean_punctuation(text: &str) -> String {
+    let chars: Vec<char> = text.chars().filter(|c| !matches!(c, '♪' | '♫' | '♬')).collect();
+    let mut result: Vec<char> = Vec::with_capacity(chars.len());
+    for (i, &c) in chars.iter().enumerate() {
+        if c.is_whitespace() {
+            let prev = result.last().copied();
+            let next = chars[i + 1..].iter().find(|c| !c.is_whitespace()).copied();
+            if let (Some(prev), Some(next)) = (prev, next) {
+                if prev.is_ascii_alphanumeric() && next.is_ascii_alphanumeric() {
+                    result.push(' ');
+                }
+            }
+            continue;
+        }
+
+        if is_pause(c) || is_stop(c) {
+            match result.last().copied() {
+                None => continue,
+                Some(last) if last == c => continue,
+                Some(last) if is_stop(last) && is_pause(c) => continue,
+                Some(last) if is_pause(last) && is_stop(c) => {
+                    result.pop();
+                }
+                _ => {}
+            }
+        }
+        result.push(c);
+    }
+    while result.last().is_some_and(|&c| is_pause(c)) {
+        result.pop();
+    }
+    result.into_iter().collect()
+}
+
+/// 漢数字の列を数値にする（「二十五」「二〇二四」「三万五千」）
+///
+/// 解釈できない並び（「二三十」など）や桁あふれはNone。
+fn parse_kanji_number(chars: &[char]) -> Option<u64> {
+    if chars.iter().all(|&c| kanji_digit(c).is_some()) {
+        // 位取り（「二〇二四」）
+        return chars
+            .iter()
+            .try_fold(0u64, |value, &c| value.checked_mul(10)?.checked_add(kanji_digit(c)?));
+    }
+
+    let mut total = 0u64;
+    let mut section = 0u64;
+    let mut current: Option<u64> = None;
+    for &c in chars {
+        if let Some(digit) = kanji_digit(c) {
+            if current.is_some() {
+                return None;
+            }
+            current = Some(digit);
+        } else if let Some(unit) = unit_value(KANJI_SMALL_UNITS, c) {
+            section = section.checked_add(current.unwrap_or(1).checked_mul(unit)?)?;
+            current = None;
+        } else if let Some(unit) = unit_value(KANJI_LARGE_UNITS, c) {
+            let value = section + current.unwrap_or(0);
+            if value == 0 {
+                return None;
+            }
+            total = total.checked_add(value.checked_mul(unit)?)?;
+            section = 0;
+            current = None;
+        }
+    }
+    total.checked_add(section + current.unwrap_or(0))
+}
+
+/// 数値を漢数字にする（10000未満は十・百・千、それ以上は万・億・兆で区切る）
+fn format_kanji_number(value: u64) -> String {
+    if value == 0 {
+        return "〇".to_string();
+    }
+
+    let mut result = String::new();
+    let sections = [
+        (1_000_000_000_000, Some('兆')),
+        (100_000_000, Some('億')),
+        (10_000, Some('万')),
+        (1, None),
+    ];
+    for (unit, unit_char) in sections {
+        let section = value / unit % 10_000;
+        if section == 0 {
+            continue;
+        }
+        for &(small_char, small_unit) in KANJI_SMALL_UNITS.iter().rev() {
+            let digit = section / small_unit % 10;
+            if digit > 1 {
+                result.push(digit_char(digit));
+            }
+            if digit > 0 {
+                result.push(small_char);
+            }
+        }
+        let ones = section % 10;
+        if ones > 0 {
+            result.push(digit_char(ones));
+        }
+        result.extend(unit_char);
+    }
+    result
+}
+
+/// 数字の間の桁区切りカンマを除く（「1,000」→「1000」）
+fn remove_digit_separators(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    chars
+        .iter()
+        .enumerate()
+        .filter(|&(i, &c)| {
+            let is_separator = c == ','
+                && i > 0
+                && chars[i - 1].is_ascii_digit()
+                && chars.len() >= i + 4
+                && chars[i + 1..i + 4].iter().all(|c| c.is_ascii_digit())
+                && !chars.get(i + 4).is_some_and(|c| c.is_ascii_digit());
+            !is_separator
+        })
+        .map(|(_, &c)| c)
+        .collect()
+}
+
+fn kanji_digit(c: char) -> Option<u64> {
+    KANJI_DIGITS.iter().find(|&&(k, _)| k == c).map(|&(_, value)| value)
+}
+
+fn unit_value(units: &[(char, u64)], c: char) -> Option<u64> {
+    units.iter().find(|&&(k, _)| k == c).map(|&(_, value)| value)
+}
+
+fn digit_char(digit: u64) -> char {
+    KANJI_DIGIT_CHARS[digit as usize]
+}
+
+fn is_kanji_numeral(c: char) -> bool {
+    kanji_digit(c).is_some() || unit_value(KANJI_SMALL_UNITS, c).is_some() || unit_value(KANJI_LARGE_UNITS, c).is_some()
+}
+
+/// 濁点付きのカタカナ
+fn voiced_kana(c: char) -> Option<char> {
+    match c {
+        'ウ' => Some('ヴ'),
+        'カ' | 'キ' | 'ク' | 'ケ' | 'コ' | 'サ' | 'シ' | 'ス' | 'セ' | 'ソ' | 'タ' | 'チ' | 'ツ' | 'テ' | 'ト' | 'ハ'
+        | 'ヒ' | 'フ' | 'ヘ' | 'ホ' => char::from_u32(c as u32 + 1),
+        _ => None,
+    }
+}
+
+/// 半濁点付きのカタカナ
+fn semi_voiced_kana(c: char) -> Option<char> {
+    match c {
+        'ハ' | 'ヒ' | 'フ' | 'ヘ' | 'ホ' => char::from_u32(c as u32 + 2),
+        _ => None,
+    }
+}
+
+/// 読点
+fn is_pause(c: char) -> bool {
+    matches!(c, '、' | ',')
+}
+
+/// 句点・感嘆符・疑問符
+fn is_stop(c: char) -> bool {
+    matches!(c, '。' | '.' | '!' | '?')
+}
+
+/// 語の区切り（空白・句読点・括弧）
+fn is_boundary(c: char) -> bool {
+    c.is_whitespace() || is_pause(c) || is_stop(c) || matches!(c, '…' | '「' | '」' | '(' | ')' | '・')
+}
@@ -0,0 +1,86 @@
+use anyhow::{Context, Result};
+use log::{debug, warn};
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::mpsc::Sender;
+use std::time::Duration;
+
+use super::{send, ManualTrigger, TriggerSignal, TriggerSource};
+
+/// リクエスト読み取りのタイムアウト
+const HTTP_READ_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// ローカルHTTPトリガーを待ち受けるスレッドを起動
+///
+/// - `POST /trigger`: 押下（聞き取り開始、toggleでは開始/停止）
+/// - `POST /ptt/down`, `POST /ptt/up`: プッシュトゥトークのボタンを押す/離す
+pub(super) fn spawn(addr: &str, sender: Sender<ManualTrigger>) -> Result<()> {
+    let listener = TcpListener::bind(addr).with_context(|| format!("HTTPトリガーを開始できません: {}", addr))?;
+
+    std::thread::Builder::new()
+        .name("trigger-http".to_string())
+        .spawn(move || {
+            for stream in listener.incoming() {
+                let stream = match stream {
+                    Ok(stream) => stream,
+                    Err(e) => {
+                        warn!("HTTPトリガー: 接続エラー: {}", e);
+                        continue;
+                    }
+                };
+                match handle_connection(stream) {
+                    Ok(Some(signal)) => {
+                        if !send(&sender, TriggerSource::Http, signal) {
+                            break;
+                        }
+                    }
+                    Ok(None) => {}
+                    Err(e) => warn!("HTTPトリガー: リクエスト処理エラー: {:#}", e),
+                }
+            }
+            debug!("HTTPトリガーを終了しました");
+        })?;
+    Ok(())
+}
+
+/// 1リクエストを処理し、対応する信号を返す
+fn handle_connection(mut stream: TcpStream) -> Result<Option<TriggerSignal>> {
+    stream.set_read_timeout(Some(HTTP_READ_TIMEOUT))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    // ヘッダーは読み捨てる（ボディは使わない）
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 0 && header != "\r\n" && header != "\n" {
+        header.clear();
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or("");
+    let path = parts.next().unwrap_or("");
+    debug!("HTTPトリガー: {} {}", method, path);
+
+    let signal = match (method, path) {
+        ("POST", "/trigger") => Some(TriggerSignal::Press),
+        ("POST", "/ptt/down") => Some(TriggerSignal::Down),
+        ("POST", "/ptt/up") => Some(TriggerSignal::Up),
+        _ => None,
+    };
+
+    let (status, body) = match signal {
+        Some(_) => ("200 OK", "ok\n"),
+        None if method == "POST" || method == "GET" => ("404 Not Found", "not found\n"),
+        None => ("405 Method Not Allowed", "method not allowed\n"),
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: text/plain\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    )?;
+    stream.flush()?;
+
+    Ok(signal)
+}
@@ -0,0 +1,24 @@
+use anyhow::Result;
+use log::debug;
+use std::io::{self, BufRead};
+use std::sync::mpsc::Sender;
+
+use super::{send, ManualTrigger, TriggerSignal, TriggerSource};
+
+/// 標準入力のEnterを押下信号として送るスレッドを起動
+///
+/// 端末は行単位入力のため、キーを離したことは検出できない（常にPressを送る）。
+pub(super) fn spawn(sender: Sender<ManualTrigger>) -> Result<()> {
+    std::thread::Builder::new()
+        .name("trigger-keyboard".to_string())
+        .spawn(move || {
+            let stdin = io::stdin();
+            for line in stdin.lock().lines() {
+                if line.is_err() || !send(&sender, TriggerSource::Keyboard, TriggerSignal::Press) {
+                    break;
+                }
+            }
+            debug!("キーボードトリガーを終了しました");
+        })?;
+    Ok(())
+}
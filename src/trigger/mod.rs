@@ -0,0 +1,123 @@
+mod http;
+mod keyboard;
+
+use anyhow::Result;
+use log::{debug, info};
+use std::sync::mpsc::{self, Receiver, Sender};
+
+use crate::config::{PushToTalkMode, TriggerConfig, TriggerSourceKind};
+
+/// ウェイクワード以外の聞き取り開始の入力元
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TriggerSource {
+    /// 標準入力のEnter
+    Keyboard,
+    /// ローカルHTTPリクエスト
+    Http,
+}
+
+/// 入力元から届く信号
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TriggerSignal {
+    /// 押下（Enter、POST /trigger）: 開始、またはプッシュトゥトークの切り替え
+    Press,
+    /// ボタンを押した（POST /ptt/down）
+    Down,
+    /// ボタンを離した（POST /ptt/up）
+    Up,
+}
+
+/// 入力元と信号の組
+#[derive(Debug, Clone, Copy)]
+struct ManualTrigger {
+    source: TriggerSource,
+    signal: TriggerSignal,
+}
+
+/// キーボード・HTTPによる聞き取り開始を1つのチャネルにまとめる
+///
+/// 各入力元は専用スレッドで待ち受け、信号を送る。
+pub struct TriggerHub {
+    signals: Receiver<ManualTrigger>,
+    push_to_talk: PushToTalkMode,
+}
+
+impl TriggerHub {
+    /// 設定から入力元を起動（ウェイクワード以外の入力元がなければNone）
+    pub fn from_config(config: &TriggerConfig) -> Result<Option<Self>> {
+        let (sender, signals) = mpsc::channel();
+        let mut started = false;
+
+        for source in &config.sources {
+            match source {
+                TriggerSourceKind::Wakeword => {}
+                TriggerSourceKind::Keyboard => {
+                    keyboard::spawn(sender.clone())?;
+                    info!("トリガー: キーボード（Enterで聞き取り開始）");
+                    started = true;
+                }
+                TriggerSourceKind::Http => {
+                    http::spawn(&config.http_addr, sender.clone())?;
+                    info!(
+                        "トリガー: HTTP (POST http://{}/trigger, /ptt/down, /ptt/up)",
+                        config.http_addr
+                    );
+                    started = true;
+                }
+            }
+        }
+
+        if !started {
+            return Ok(None);
+        }
+        info!("プッシュトゥトーク: {:?}", config.push_to_talk);
+
+        Ok(Some(Self {
+            signals,
+            push_to_talk: config.push_to_talk,
+        }))
+    }
+
+    /// プッシュトゥトークのモード
+    pub fn push_to_talk(&self) -> PushToTalkMode {
+        self.push_to_talk
+    }
+
+    /// 開始信号があれば入力元を返す（待機しない）
+    ///
+    /// 開始に関係しない信号（離す）は読み捨てる。
+    pub fn try_recv_start(&self) -> Option<TriggerSource> {
+        while let Ok(trigger) = self.signals.try_recv() {
+            match trigger.signal {
+                TriggerSignal::Press | TriggerSignal::Down => return Some(trigger.source),
+                TriggerSignal::Up => debug!("待機中のUp信号を無視 ({:?})", trigger.source),
+            }
+        }
+        None
+    }
+
+    /// プッシュトゥトークの録音停止が要求されたか（溜まっている信号を消費する）
+    ///
+    /// - hold: 離す（Up）、またはキーボードのEnter（離したことを検出できないため）で停止
+    /// - toggle: 再度の押下（Press/Down）で停止
+    pub fn stop_requested(&self) -> bool {
+        let mut stop = false;
+        while let Ok(trigger) = self.signals.try_recv() {
+            stop |= matches!(
+                (self.push_to_talk, trigger.signal),
+                (PushToTalkMode::Hold, TriggerSignal::Up | TriggerSignal::Press)
+                    | (PushToTalkMode::Toggle, TriggerSignal::Press | TriggerSignal::Down)
+            );
+        }
+        stop
+    }
+
+    /// 溜まっている信号を破棄（処理中の押下で次の聞き取りが始まらないようにする）
+    pub fn drain(&self) {
+        while self.signals.try_recv().is_ok() {}
+    }
+}
+
+fn send(sender: &Sender<ManualTrigger>, source: TriggerSource, signal: TriggerSignal) -> bool {
+    sender.send(ManualTrigger { source, signal }).is_ok()
+}
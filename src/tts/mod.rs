@@ -1,3 +1,5 @@
+mod openai;
+mod router;
 mod voicevox;
 
-pub use voicevox::VoicevoxTts;
+pub use router::TtsRouter;
@@ -0,0 +1,102 @@
+use anyhow::Result;
+use log::{debug, info};
+use reqwest::blocking::Client;
+use serde::Serialize;
+use thiserror::Error;
+
+/// OpenAI互換TTSに関するエラー
+#[derive(Debug, Error)]
+pub enum OpenAiTtsError {
+    #[error("音声合成APIへの接続に失敗: {0}")]
+    ConnectionError(String),
+
+    #[error("音声合成に失敗: {0}")]
+    SynthesisError(String),
+}
+
+/// 音声合成APIリクエスト（POST /v1/audio/speech）
+#[derive(Debug, Serialize)]
+struct SpeechRequest<'a> {
+    model: &'a str,
+    input: &'a str,
+    voice: &'a str,
+    response_format: &'a str,
+    speed: f32,
+}
+
+/// OpenAI互換の音声合成API（Kokoro-FastAPI、openedai-speechなど）を使用した音声合成エンジン
+pub struct OpenAiTts {
+    client: Client,
+    endpoint: String,
+    model: String,
+    voice: String,
+    api_key: Option<String>,
+    speed: f32,
+}
+
+impl OpenAiTts {
+    /// OpenAiTtsインスタンスを生成
+    ///
+    /// # Arguments
+    /// * `endpoint` - エンドポイントURL（`/v1/audio/speech`より前の部分）
+    /// * `model` - モデル名
+    /// * `voice` - 音声名
+    /// * `api_key` - APIキー（不要なサーバーではNone）
+    /// * `speed` - 話速
+    pub fn new(endpoint: &str, model: &str, voice: &str, api_key: Option<&str>, speed: f32) -> Self {
+        info!("OpenAI互換TTS初期化: endpoint={}, model={}, voice={}", endpoint, model, voice);
+
+        Self {
+            client: Client::new(),
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+            model: model.to_string(),
+            voice: voice.to_string(),
+            api_key: api_key.map(str::to_string),
+            speed,
+        }
+    }
+
+    /// テキストを音声データに変換
+    ///
+    /// # Arguments
+    /// * `text` - 合成するテキスト
+    ///
+    /// # Returns
+    /// WAV形式の音声データ（バイト列）
+    pub fn synthesize(&self, text: &str) -> Result<Vec<u8>> {
+        debug!("音声合成開始 (voice={}): \"{}\"", self.voice, text);
+
+        let url = format!("{}/v1/audio/speech", self.endpoint);
+        let request = SpeechRequest {
+            model: &self.model,
+            input: text,
+            voice: &self.voice,
+            response_format: "wav",
+            speed: self.speed,
+        };
+
+        let mut builder = self.client.post(&url).json(&request);
+        if let Some(api_key) = &self.api_key {
+            builder = builder.bearer_auth(api_key);
+        }
+        let response = builder
+            .send()
+            .map_err(|e| OpenAiTtsError::ConnectionError(e.to_string()))?;
+
+        if !response.status().is_success() {
+            return Err(OpenAiTtsError::SynthesisError(format!(
+                "ステータスコード: {}",
+                response.status()
+            ))
+            .into());
+        }
+
+        let audio = response
+            .bytes()
+            .map_err(|e| OpenAiTtsError::SynthesisError(e.to_string()))?
+            .to_vec();
+
+        debug!("音声合成完了: {} bytes", audio.len());
+        Ok(audio)
+    }
+}
@@ -0,0 +1,79 @@
+use anyhow::Result;
+use log::{debug, info};
+use std::collections::HashMap;
+
+use super::openai::OpenAiTts;
+use super::voicevox::VoicevoxTts;
+use crate::config::{TtsConfig, VoiceConfig};
+
+/// 言語ごとの音声
+enum Voice {
+    /// VOICEVOX（Noneはプロファイルの話者）
+    Voicevox(Option<i32>),
+    /// OpenAI互換の音声合成API
+    OpenAi(OpenAiTts),
+}
+
+/// 認識言語に応じて音声合成エンジンを切り替えるTTS
+///
+/// `[tts.voices]`に指定のない言語（または言語不明）はVOICEVOXで合成する。
+pub struct TtsRouter {
+    voicevox: VoicevoxTts,
+    voices: HashMap<String, Voice>,
+}
+
+impl TtsRouter {
+    /// 設定からTtsRouterインスタンスを生成
+    ///
+    /// # Arguments
+    /// * `config` - TTS設定
+    pub fn new(config: &TtsConfig) -> Result<Self> {
+        let voicevox = VoicevoxTts::new(config)?;
+
+        let mut voices = HashMap::new();
+        for (language, voice_config) in &config.voices {
+            let voice = match voice_config {
+                VoiceConfig::Voicevox { speaker_id } => {
+                    info!("TTS音声: language={} -> VOICEVOX (speaker_id={:?})", language, speaker_id);
+                    Voice::Voicevox(*speaker_id)
+                }
+                VoiceConfig::Openai {
+                    endpoint,
+                    model,
+                    voice,
+                    api_key,
+                } => {
+                    info!("TTS音声: language={} -> OpenAI互換 ({})", language, endpoint);
+                    Voice::OpenAi(OpenAiTts::new(endpoint, model, voice, api_key.as_deref(), config.speed))
+                }
+            };
+            voices.insert(language.clone(), voice);
+        }
+
+        Ok(Self { voicevox, voices })
+    }
+
+    /// 言語に応じた音声でテキストを音声データに変換
+    ///
+    /// # Arguments
+    /// * `text` - 合成するテキスト
+    /// * `speaker_id` - VOICEVOXの話者ID（プロファイルの話者）
+    /// * `language` - 認識言語（Whisperの言語コード）
+    ///
+    /// # Returns
+    /// WAV形式の音声データ（バイト列）
+    pub fn synthesize(&self, text: &str, speaker_id: i32, language: Option<&str>) -> Result<Vec<u8>> {
+        let voice = language.and_then(|language| self.voices.get(language));
+        debug!("TTS音声選択: language={:?}, 言語別設定={}", language, voice.is_some());
+        match voice {
+            Some(Voice::OpenAi(tts)) => tts.synthesize(text),
+            Some(Voice::Voicevox(Some(id))) => self.voicevox.synthesize_as(text, *id),
+            Some(Voice::Voicevox(None)) | None => self.voicevox.synthesize_as(text, speaker_id),
+        }
+    }
+
+    /// VOICEVOXサーバーの接続確認
+    pub fn health_check(&self) -> Result<bool> {
+        self.voicevox.health_check()
+    }
+}
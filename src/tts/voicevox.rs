@@ -23,7 +23,6 @@ pub enum TtsError {
 pub struct VoicevoxTts {
     client: Client,
     endpoint: String,
-    speaker_id: i32,
     speed: f32,
 }
 
@@ -43,38 +42,38 @@ impl VoicevoxTts {
         Ok(Self {
             client,
             endpoint: config.endpoint.clone(),
-            speaker_id: config.speaker_id,
             speed: config.speed,
         })
     }
 
-    /// テキストを音声データに変換
+    /// 話者IDを指定してテキストを音声データに変換
     ///
     /// # Arguments
     /// * `text` - 合成するテキスト
+    /// * `speaker_id` - VOICEVOXの話者ID
     ///
     /// # Returns
     /// WAV形式の音声データ（バイト列）
-    pub fn synthesize(&self, text: &str) -> Result<Vec<u8>> {
-        debug!("音声合成開始: \"{}\"", text);
+    pub fn synthesize_as(&self, text: &str, speaker_id: i32) -> Result<Vec<u8>> {
+        debug!("音声合成開始 (speaker_id={}): \"{}\"", speaker_id, text);
 
         // 1. audio_queryを作成
-        let query = self.create_audio_query(text)?;
+        let query = self.create_audio_query(text, speaker_id)?;
 
         // 2. 音声合成を実行
-        let audio = self.synthesis(&query)?;
+        let audio = self.synthesis(&query, speaker_id)?;
 
         debug!("音声合成完了: {} bytes", audio.len());
         Ok(audio)
     }
 
     /// 音声合成用クエリを作成
-    fn create_audio_query(&self, text: &str) -> Result<Value> {
+    fn create_audio_query(&self, text: &str, speaker_id: i32) -> Result<Value> {
         let url = format!(
             "{}/audio_query?text={}&speaker={}",
             self.endpoint,
             urlencoding::encode(text),
-            self.speaker_id
+            speaker_id
         );
 
         debug!("audio_query API呼び出し: {}", url);
@@ -106,8 +105,8 @@ impl VoicevoxTts {
     }
 
     /// 音声合成を実行
-    fn synthesis(&self, query: &Value) -> Result<Vec<u8>> {
-        let url = format!("{}/synthesis?speaker={}", self.endpoint, self.speaker_id);
+    fn synthesis(&self, query: &Value, speaker_id: i32) -> Result<Vec<u8>> {
+        let url = format!("{}/synthesis?speaker={}", self.endpoint, speaker_id);
 
         debug!("synthesis API呼び出し");
 
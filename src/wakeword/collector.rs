@@ -0,0 +1,230 @@
+use anyhow::{Context, Result};
+use log::{debug, info};
+use serde::Serialize;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use super::engine::FrameResult;
+use super::sensitivity::Thresholds;
+use crate::audio::wav;
+use crate::config::WakewordConfig;
+
+/// クリップのサンプルレート（検出器への入力と同じ）
+const CLIP_SAMPLE_RATE: u32 = 16000;
+
+/// 収集するクリップの種類（保存先のサブディレクトリ名）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ClipKind {
+    /// 閾値に迫ったが検出に至らなかった（見逃し候補）
+    NearMiss,
+    /// 検出後のコマンドが空だった（誤検出候補）
+    EmptyCommand,
+    /// 二段階検証で棄却された（誤検出候補）
+    VerifierRejected,
+}
+
+impl ClipKind {
+    fn dir_name(self) -> &'static str {
+        match self {
+            ClipKind::NearMiss => "near_miss",
+            ClipKind::EmptyCommand => "empty_command",
+            ClipKind::VerifierRejected => "verifier_rejected",
+        }
+    }
+}
+
+/// クリップに添付するメタデータ（WAVと同名の.json）
+#[derive(Debug, Serialize)]
+struct ClipMetadata<'a> {
+    kind: ClipKind,
+    keyword: &'a str,
+    /// 検出スコア（near_missは部分検出スコアのピーク）
+    score: f32,
+    /// 収集時に適用されていた検出閾値（感度スケジュール・自動調整を反映）
+    threshold: f32,
+    avg_threshold: f32,
+    min_scores: usize,
+    /// 収集時刻（UNIX時間、秒）
+    timestamp: f64,
+    sample_rate: u32,
+    duration_secs: f32,
+    /// 二段階検証の認識結果など
+    #[serde(skip_serializing_if = "Option::is_none")]
+    transcript: Option<&'a str>,
+}
+
+/// 再学習・閾値調整用のウェイクワードクリップ収集
+///
+/// 検出スレッド（near_miss）とメインループ（誤検出候補）の両方から使うためClone可能。
+#[derive(Clone)]
+pub struct ClipCollector {
+    dir: PathBuf,
+    /// 適用中の検出閾値（検出スレッドが更新する）
+    thresholds: Arc<Mutex<Thresholds>>,
+    near_miss_level: f32,
+    near_miss_cooldown: Duration,
+    counter: Arc<AtomicU64>,
+}
+
+impl ClipCollector {
+    /// 設定から生成（収集が無効ならNone）
+    pub fn from_config(config: &WakewordConfig) -> Result<Option<Self>> {
+        let collection = &config.collection;
+        if !collection.enabled {
+            return Ok(None);
+        }
+
+        let dir = PathBuf::from(&collection.dir);
+        for kind in [ClipKind::NearMiss, ClipKind::EmptyCommand, ClipKind::VerifierRejected] {
+            let kind_dir = dir.join(kind.dir_name());
+            std::fs::create_dir_all(&kind_dir)
+                .with_context(|| format!("クリップ保存先を作成できません: {}", kind_dir.display()))?;
+        }
+
+        let near_miss_level = config.detection_threshold() * collection.near_miss_ratio;
+        info!(
+            "ウェイクワードクリップ収集: 有効 (dir={}, near_miss_level={:.3}, cooldown={:.1}秒)",
+            dir.display(),
+            near_miss_level,
+            collection.near_miss_cooldown
+        );
+
+        Ok(Some(Self {
+            dir,
+            thresholds: Arc::new(Mutex::new(Thresholds {
+                threshold: config.detection_threshold(),
+                avg_threshold: config.avg_threshold,
+                min_scores: config.min_scores,
+            })),
+            near_miss_level,
+            near_miss_cooldown: Duration::from_secs_f32(collection.near_miss_cooldown.max(0.0)),
+            counter: Arc::new(AtomicU64::new(0)),
+        }))
+    }
+
+    /// 適用中の検出閾値を更新（メタデータに記録する値）
+    pub fn set_thresholds(&self, thresholds: Thresholds) {
+        *self.thresholds.lock().unwrap() = thresholds;
+    }
+
+    /// クリップ（16kHz音声）とメタデータを保存
+    pub fn save(
+        &self,
+        kind: ClipKind,
+        keyword: &str,
+        score: f32,
+        audio: &[f32],
+        timestamp: SystemTime,
+        transcript: Option<&str>,
+    ) -> Result<PathBuf> {
+        let unix_time = timestamp.duration_since(UNIX_EPOCH).unwrap_or_default();
+        let seq = self.counter.fetch_add(1, Ordering::Relaxed);
+        let stem = format!("{}_{:03}_{}", unix_time.as_millis(), seq % 1000, sanitize(keyword));
+
+        let kind_dir = self.dir.join(kind.dir_name());
+        let wav_path = kind_dir.join(format!("{}.wav", stem));
+        wav::write_wav_i16(&wav_path, audio, CLIP_SAMPLE_RATE)?;
+
+        let thresholds = *self.thresholds.lock().unwrap();
+        let metadata = ClipMetadata {
+            kind,
+            keyword,
+            score,
+            threshold: thresholds.threshold,
+            avg_threshold: thresholds.avg_threshold,
+            min_scores: thresholds.min_scores,
+            timestamp: unix_time.as_secs_f64(),
+            sample_rate: CLIP_SAMPLE_RATE,
+            duration_secs: audio.len() as f32 / CLIP_SAMPLE_RATE as f32,
+            transcript,
+        };
+        let json_path = kind_dir.join(format!("{}.json", stem));
+        std::fs::write(&json_path, serde_json::to_string_pretty(&metadata)?)
+            .with_context(|| format!("メタデータの保存に失敗: {}", json_path.display()))?;
+
+        info!("クリップを保存 ({:?}): {} (score={:.3})", kind, wav_path.display(), score);
+        Ok(wav_path)
+    }
+
+    /// 検出スレッド用のnear_miss判定器を生成
+    pub fn near_miss_tracker(&self) -> NearMissTracker {
+        NearMissTracker {
+            level: self.near_miss_level,
+            cooldown: self.near_miss_cooldown,
+            episode: None,
+            last_saved: None,
+        }
+    }
+}
+
+/// 閾値に迫った部分検出の区間
+struct NearMissEpisode {
+    keyword: String,
+    peak_score: f32,
+    detected: bool,
+}
+
+/// 確定したnear_miss
+pub struct NearMiss {
+    pub keyword: String,
+    pub peak_score: f32,
+}
+
+/// 部分検出スコアを追跡し、検出に至らなかったピークをnear_missとして報告する
+pub struct NearMissTracker {
+    level: f32,
+    cooldown: Duration,
+    episode: Option<NearMissEpisode>,
+    last_saved: Option<Instant>,
+}
+
+impl NearMissTracker {
+    /// 1フレーム分の結果で更新（near_missが確定したフレームでSomeを返す）
+    ///
+    /// 部分検出スコアがレベルを超えてから下回るまでを1区間とし、
+    /// その間に検出がなければ区間の終わりで確定する。
+    pub fn update(&mut self, frame: &FrameResult) -> Option<NearMiss> {
+        if frame.partial_score >= self.level {
+            let episode = self.episode.get_or_insert_with(|| NearMissEpisode {
+                keyword: "unknown".to_string(),
+                peak_score: 0.0,
+                detected: false,
+            });
+            if frame.partial_score > episode.peak_score {
+                episode.peak_score = frame.partial_score;
+                if let Some(keyword) = &frame.partial_keyword {
+                    episode.keyword = keyword.clone();
+                }
+            }
+            if frame.detection.is_some() {
+                episode.detected = true;
+            }
+            return None;
+        }
+
+        let episode = self.episode.take()?;
+        if episode.detected || frame.detection.is_some() {
+            return None;
+        }
+        if self.last_saved.is_some_and(|t| t.elapsed() < self.cooldown) {
+            debug!("near_miss (クールダウン中のため保存しない): peak={:.3}", episode.peak_score);
+            return None;
+        }
+
+        self.last_saved = Some(Instant::now());
+        Some(NearMiss {
+            keyword: episode.keyword,
+            peak_score: episode.peak_score,
+        })
+    }
+}
+
+/// ファイル名に使えない文字を置き換える
+fn sanitize(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect()
+}
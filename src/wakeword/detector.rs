@@ -1,18 +1,11 @@
 use anyhow::Result;
-use log::{debug, info};
-use rustpotter::{Rustpotter, RustpotterConfig, SampleFormat};
-use std::io::{self, Write};
-
-use crate::audio::AudioCapture;
-use crate::config::WakewordConfig;
-
-/// ウェイクワード検出結果
-pub struct WakewordResult {
-    /// 検出されたウェイクワード名
-    pub keyword: String,
-    /// 検出スコア（0.0〜1.0）
-    pub score: f32,
-}
+use log::{debug, info, warn};
+use rustpotter::{Rustpotter, RustpotterConfig, SampleFormat, ScoreMode, VADMode};
+
+use super::engine::{frame_amplitude, frame_rms, FrameResult, SelfTriggerGuard, WakewordEngine, WakewordResult};
+use super::sensitivity::SensitivityController;
+use crate::audio::SpeakingState;
+use crate::config::{self, WakewordConfig};
 
 /// 起動直後にスキップするフレーム数（誤検出防止）
 /// 100 (~0.3秒) → 300 (~1秒) に増加
@@ -34,25 +27,48 @@ const VAD_SILENCE_GAIN: f32 = 0.1;
 pub struct WakewordDetector {
     rustpotter: Rustpotter,
     samples_per_frame: usize,
+    /// 独自の前処理（正規化 + VAD）を行うか
+    custom_preprocessing: bool,
+    /// 応答再生中の自己検出対策
+    self_trigger: SelfTriggerGuard,
+    /// 時間帯・環境ノイズによる感度調整
+    sensitivity: Option<SensitivityController>,
 }
 
 impl WakewordDetector {
     /// 設定からWakewordDetectorを生成
     pub fn new(config: &WakewordConfig) -> Result<Self> {
-        // モデルファイルの存在確認
-        let wakeword_path = std::path::Path::new(&config.wakeword_path);
-        if !wakeword_path.exists() {
-            // カレントディレクトリからの相対パスを試す
-            let cwd = std::env::current_dir().unwrap_or_default();
-            let full_path = cwd.join(&config.wakeword_path);
-            if !full_path.exists() {
-                return Err(anyhow::anyhow!(
-                    "ウェイクワードファイルが見つかりません: {} (cwd: {})",
-                    config.wakeword_path,
-                    cwd.display()
-                ));
+        let entries = config.entries();
+        if entries.is_empty() {
+            return Err(anyhow::anyhow!(
+                "ウェイクワードが設定されていません（wakeword_path または keywords を指定してください）"
+            ));
+        }
+
+        // モデルファイルの存在確認（キーワード名, パス）
+        let mut models: Vec<(String, &str)> = Vec::with_capacity(entries.len());
+        for entry in &entries {
+            let path = entry.path.as_deref().ok_or_else(|| {
+                anyhow::anyhow!(
+                    "ウェイクワード \"{}\" の path（.rpwファイル）が指定されていません（engine = \"rustpotter\" では必須）",
+                    entry.key()
+                )
+            })?;
+            let wakeword_path = std::path::Path::new(path);
+            if !wakeword_path.exists() {
+                // カレントディレクトリからの相対パスを試す
+                let cwd = std::env::current_dir().unwrap_or_default();
+                let full_path = cwd.join(path);
+                if !full_path.exists() {
+                    return Err(anyhow::anyhow!(
+                        "ウェイクワードファイルが見つかりません: {} (cwd: {})",
+                        path,
+                        cwd.display()
+                    ));
+                }
+                info!("ウェイクワードファイル解決: {} -> {}", path, full_path.display());
             }
-            info!("ウェイクワードファイル解決: {} -> {}", config.wakeword_path, full_path.display());
+            models.push((entry.key(), path));
         }
 
         // Rustpotter設定を初期化
@@ -69,29 +85,66 @@ impl WakewordDetector {
         // 連続検出回数を設定（単発の誤検出を防ぐ）
         rustpotter_config.detector.min_scores = config.min_scores;
 
+        // 感度調整が有効ならRustpotterは最も低い閾値で動かし、検出後に現在の閾値で絞り込む
+        let sensitivity = SensitivityController::from_config(config)?;
+        if let Some(sensitivity) = &sensitivity {
+            let floor = sensitivity.floor_thresholds();
+            rustpotter_config.detector.threshold = floor.threshold;
+            rustpotter_config.detector.avg_threshold = floor.avg_threshold;
+            rustpotter_config.detector.min_scores = floor.min_scores;
+        }
+
+        // 詳細設定（指定された項目のみ上書き）
+        Self::apply_advanced_options(&mut rustpotter_config, config);
+
         info!(
             "Rustpotter設定: threshold={}, avg_threshold={}, min_scores={}",
-            config.threshold, config.avg_threshold, config.min_scores
+            rustpotter_config.detector.threshold,
+            rustpotter_config.detector.avg_threshold,
+            rustpotter_config.detector.min_scores
         );
+        info!(
+            "Rustpotter詳細設定: eager={}, score_ref={}, band_size={}, score_mode={:?}, vad_mode={:?}, \
+             gain_normalizer={} (ref={:?}, {}〜{}), band_pass={} ({}〜{}Hz), custom_preprocessing={}",
+            rustpotter_config.detector.eager,
+            rustpotter_config.detector.score_ref,
+            rustpotter_config.detector.band_size,
+            rustpotter_config.detector.score_mode,
+            rustpotter_config.detector.vad_mode,
+            rustpotter_config.filters.gain_normalizer.enabled,
+            rustpotter_config.filters.gain_normalizer.gain_ref,
+            rustpotter_config.filters.gain_normalizer.min_gain,
+            rustpotter_config.filters.gain_normalizer.max_gain,
+            rustpotter_config.filters.band_pass.enabled,
+            rustpotter_config.filters.band_pass.low_cutoff,
+            rustpotter_config.filters.band_pass.high_cutoff,
+            config.custom_preprocessing
+        );
+        if config.custom_preprocessing && rustpotter_config.filters.gain_normalizer.enabled {
+            warn!("独自の音量正規化とRustpotterのゲイン正規化が両方有効です（custom_preprocessing = false を推奨）");
+        }
 
         // Rustpotterインスタンスを作成
         let mut rustpotter = Rustpotter::new(&rustpotter_config)
             .map_err(|e| anyhow::anyhow!("Rustpotterの初期化に失敗: {}", e))?;
 
-        // ウェイクワードファイルを読み込み（keyはファイル名から自動生成）
-        let wakeword_key = std::path::Path::new(&config.wakeword_path)
-            .file_stem()
-            .and_then(|s| s.to_str())
-            .unwrap_or("wakeword");
-        rustpotter
-            .add_wakeword_from_file(wakeword_key, &config.wakeword_path)
-            .map_err(|e| anyhow::anyhow!("ウェイクワードファイルの読み込みに失敗: {} - {}", config.wakeword_path, e))?;
+        // ウェイクワードファイルを読み込み（keyは検出結果のkeywordになる）
+        let mut keys: Vec<String> = Vec::with_capacity(models.len());
+        for (wakeword_key, path) in models {
+            if keys.contains(&wakeword_key) {
+                return Err(anyhow::anyhow!("ウェイクワード名が重複しています: {}", wakeword_key));
+            }
+            rustpotter
+                .add_wakeword_from_file(&wakeword_key, path)
+                .map_err(|e| anyhow::anyhow!("ウェイクワードファイルの読み込みに失敗: {} - {}", path, e))?;
+            keys.push(wakeword_key);
+        }
 
         let samples_per_frame = rustpotter.get_samples_per_frame();
 
         info!(
-            "ウェイクワード検出器初期化完了: keyword=\"{}\", samples_per_frame={}, frame_duration={:.1}ms",
-            wakeword_key,
+            "ウェイクワード検出器初期化完了: keywords={:?}, samples_per_frame={}, frame_duration={:.1}ms",
+            keys,
             samples_per_frame,
             samples_per_frame as f32 / 16.0 // 16kHz -> ms
         );
@@ -99,112 +152,71 @@ impl WakewordDetector {
         Ok(Self {
             rustpotter,
             samples_per_frame,
+            custom_preprocessing: config.custom_preprocessing,
+            self_trigger: SelfTriggerGuard::from_config(config),
+            sensitivity,
         })
     }
 
-    /// ウェイクワードを検出するまで待機
-    pub fn wait_for_wakeword(&mut self, capture: &AudioCapture) -> Result<WakewordResult> {
-        println!();
-        println!("========================================");
-        println!("  Waiting for wakeword...");
-        println!("========================================");
-        println!();
-
-        // ストリーム読み取り位置をリセット（連続フレーム読み取りのため）
-        capture.reset_stream_position();
-        debug!("ストリーム読み取り位置をリセット");
-
-        let mut frame_count = 0u64;
-        let mut max_rms_seen: f32 = 0.0;
-        let mut max_score_seen: f32 = 0.0;
-
-        loop {
-            frame_count += 1;
-
-            // フレーム分の音声を取得（連続、重複なし）
-            let raw_samples = capture.record_samples(self.samples_per_frame)?;
-
-            // 前処理パイプライン（正規化 + VAD）
-            let samples = Self::preprocess_samples(&raw_samples);
-
-            // デバッグ: 音声レベルとサンプル数（前処理後）
-            let rms: f32 = if !samples.is_empty() {
-                let sum: f64 = samples.iter().map(|&s| (s as f64) * (s as f64)).sum();
-                ((sum / samples.len() as f64).sqrt() / i16::MAX as f64) as f32
-            } else {
-                0.0
+    /// [wakeword]の詳細設定をRustpotter設定に反映（未指定の項目はデフォルトのまま）
+    fn apply_advanced_options(rustpotter_config: &mut RustpotterConfig, config: &WakewordConfig) {
+        let detector = &mut rustpotter_config.detector;
+        if let Some(eager) = config.eager {
+            detector.eager = eager;
+        }
+        if let Some(score_ref) = config.score_ref {
+            detector.score_ref = score_ref;
+        }
+        if let Some(band_size) = config.band_size {
+            detector.band_size = band_size;
+        }
+        if let Some(score_mode) = config.score_mode {
+            detector.score_mode = match score_mode {
+                config::ScoreMode::Max => ScoreMode::Max,
+                config::ScoreMode::Avg => ScoreMode::Average,
+                config::ScoreMode::Median => ScoreMode::Median,
+                config::ScoreMode::P25 => ScoreMode::P25,
+                config::ScoreMode::P50 => ScoreMode::P50,
+                config::ScoreMode::P75 => ScoreMode::P75,
+                config::ScoreMode::P80 => ScoreMode::P80,
+                config::ScoreMode::P90 => ScoreMode::P90,
+                config::ScoreMode::P95 => ScoreMode::P95,
             };
+        }
+        if let Some(vad_mode) = config.vad_mode {
+            detector.vad_mode = match vad_mode {
+                config::VadMode::Off => None,
+                config::VadMode::Easy => Some(VADMode::Easy),
+                config::VadMode::Medium => Some(VADMode::Medium),
+                config::VadMode::Hard => Some(VADMode::Hard),
+            };
+        }
 
-            // ウォームアップ期間中は検出をスキップ
-            if frame_count <= WARMUP_FRAMES {
-                if frame_count == 1 {
-                    print!("\r  [Warming up] frames:{}/{}    ", frame_count, WARMUP_FRAMES);
-                } else if frame_count % 10 == 0 {
-                    print!("\r  [Warming up] frames:{}/{}    ", frame_count, WARMUP_FRAMES);
-                }
-                let _ = io::stdout().flush();
-
-                // Rustpotterの内部状態を更新するが、検出結果は無視
-                let _ = self.rustpotter.process_samples(samples);
-                continue;
-            }
-
-            // ウォームアップ完了後、最初のフレームでメッセージ表示
-            if frame_count == WARMUP_FRAMES + 1 {
-                println!();
-                println!("  [Ready] Say the wakeword!");
-            }
-
-            // サンプル統計情報（デバッグ用）
-            let sample_max = samples.iter().max().copied().unwrap_or(0);
-            let sample_min = samples.iter().min().copied().unwrap_or(0);
-
-            // Rustpotterで検出処理
-            let detection = self.rustpotter.process_samples(samples.clone());
-
-            // 部分検出スコアを取得（閾値未達でもスコアを確認）
-            let partial = self.rustpotter.get_partial_detection();
-            let partial_score = partial.as_ref().map(|p| p.score).unwrap_or(0.0);
-
-            // 最大値を追跡（診断用）
-            if rms > max_rms_seen {
-                max_rms_seen = rms;
-            }
-            if partial_score > max_score_seen {
-                max_score_seen = partial_score;
-            }
-
-            // 毎フレーム出力（部分スコアも表示、最大値も表示）
-            print!(
-                "\r  [Listening] rms:{:.4} (max:{:.4}) score:{:.3} (max:{:.3}) amp:[{},{}]    ",
-                rms, max_rms_seen, partial_score, max_score_seen, sample_min, sample_max
-            );
-            let _ = io::stdout().flush();
-
-            if let Some(detection) = detection {
-                let keyword = detection.name.clone();
-                let score = detection.score;
-
-                println!();
-                println!("  >>> WAKEWORD DETECTED! <<<");
-                info!(
-                    "ウェイクワード検出 (Rustpotter): keyword=\"{}\", score={:.3}",
-                    keyword, score
-                );
-                println!("  Keyword: \"{}\"", keyword);
-                println!("  Score: {:.3}", score);
-                println!();
-
-                return Ok(WakewordResult { keyword, score });
-            }
-
-            debug!("検出なし (処理継続)");
+        let filters = &config.filters;
+        let gain_normalizer = &mut rustpotter_config.filters.gain_normalizer;
+        if let Some(enabled) = filters.gain_normalizer {
+            gain_normalizer.enabled = enabled;
+        }
+        if filters.gain_ref.is_some() {
+            gain_normalizer.gain_ref = filters.gain_ref;
+        }
+        if let Some(min_gain) = filters.min_gain {
+            gain_normalizer.min_gain = min_gain;
+        }
+        if let Some(max_gain) = filters.max_gain {
+            gain_normalizer.max_gain = max_gain;
         }
-    }
 
-    /// フレームあたりのサンプル数を取得
-    pub fn get_samples_per_frame(&self) -> usize {
-        self.samples_per_frame
+        let band_pass = &mut rustpotter_config.filters.band_pass;
+        if let Some(enabled) = filters.band_pass {
+            band_pass.enabled = enabled;
+        }
+        if let Some(low_cutoff) = filters.low_cutoff {
+            band_pass.low_cutoff = low_cutoff;
+        }
+        if let Some(high_cutoff) = filters.high_cutoff {
+            band_pass.high_cutoff = high_cutoff;
+        }
     }
 
     /// 音量正規化（i16サンプル用）
@@ -280,3 +292,73 @@ impl WakewordDetector {
         Self::apply_vad(&normalized)
     }
 }
+
+impl WakewordEngine for WakewordDetector {
+    fn name(&self) -> &'static str {
+        "Rustpotter"
+    }
+
+    /// 1フレーム分の音声を前処理してRustpotterで検出処理
+    fn process_frame(&mut self, raw_samples: &[i16]) -> FrameResult {
+        // 環境ノイズの推定用（前処理前）
+        let raw_rms = frame_rms(raw_samples);
+
+        // 前処理パイプライン（正規化 + VAD、無効時はそのままRustpotterへ）
+        let samples = if self.custom_preprocessing {
+            Self::preprocess_samples(raw_samples)
+        } else {
+            raw_samples.to_vec()
+        };
+
+        // 音声レベル（前処理後）
+        let rms = frame_rms(&samples);
+
+        // サンプル統計情報（デバッグ用）
+        let amplitude = frame_amplitude(&samples);
+
+        // Rustpotterで検出処理
+        let detection = self.rustpotter.process_samples(samples);
+
+        // 部分検出スコアを取得（閾値未達でもスコアを確認）
+        let partial = self.rustpotter.get_partial_detection();
+        let partial_score = partial.as_ref().map(|p| p.score).unwrap_or(0.0);
+        let partial_keyword = partial.as_ref().map(|p| p.name.clone());
+
+        // 感度調整（現在の閾値を満たさない検出は棄却）
+        let detection = match self.sensitivity.as_mut() {
+            Some(sensitivity) => {
+                sensitivity.observe(raw_rms, partial.is_some() || detection.is_some());
+                detection.filter(|d| sensitivity.accepts(d))
+            }
+            None => detection,
+        };
+
+        let detection = detection
+            .map(|d| WakewordResult {
+                keyword: d.name.clone(),
+                score: d.score,
+            })
+            .filter(|result| self.self_trigger.accepts(result));
+
+        FrameResult {
+            detection,
+            partial_score,
+            partial_keyword,
+            rms,
+            amplitude,
+            thresholds: self.sensitivity.as_ref().map(SensitivityController::current),
+        }
+    }
+
+    fn get_samples_per_frame(&self) -> usize {
+        self.samples_per_frame
+    }
+
+    fn warmup_frames(&self) -> u64 {
+        WARMUP_FRAMES
+    }
+
+    fn set_speaking_state(&mut self, speaking: SpeakingState) {
+        self.self_trigger.set_speaking_state(speaking);
+    }
+}
@@ -0,0 +1,137 @@
+use anyhow::Result;
+use log::{debug, info};
+use std::time::Duration;
+
+use super::detector::WakewordDetector;
+use super::sensitivity::Thresholds;
+use super::spotter::WhisperSpotter;
+use crate::audio::SpeakingState;
+use crate::config::{SelfTriggerMode, SttConfig, WakewordConfig, WakewordEngineKind};
+
+/// ウェイクワード検出結果
+pub struct WakewordResult {
+    /// 検出されたウェイクワード名
+    pub keyword: String,
+    /// 検出スコア（0.0〜1.0）
+    pub score: f32,
+}
+
+/// 1フレーム分の検出処理結果
+pub struct FrameResult {
+    /// 検出結果（閾値を満たした場合のみ）
+    pub detection: Option<WakewordResult>,
+    /// 部分検出スコア（閾値未達でも確認用に取得）
+    pub partial_score: f32,
+    /// 部分検出中のウェイクワード名
+    pub partial_keyword: Option<String>,
+    /// 前処理後のRMS（0.0〜1.0）
+    pub rms: f32,
+    /// 前処理後のサンプルの最小値・最大値
+    pub amplitude: (i16, i16),
+    /// 適用中の検出閾値（感度スケジュール・自動調整がある場合のみ）
+    pub thresholds: Option<Thresholds>,
+}
+
+/// ウェイクワード検出エンジン
+///
+/// 16kHz/i16のフレームを順に受け取り、フレームごとに検出結果を返す。
+/// キャプチャ・検出スレッド・検証・収集は全エンジン共通で使う。
+pub trait WakewordEngine: Send {
+    /// エンジン名（ログ表示用）
+    fn name(&self) -> &'static str;
+
+    /// 1フレーム分の音声を検出処理
+    ///
+    /// # Arguments
+    /// * `raw_samples` - 16kHz/i16のフレーム（`get_samples_per_frame()`サンプル）
+    fn process_frame(&mut self, raw_samples: &[i16]) -> FrameResult;
+
+    /// フレームあたりのサンプル数を取得
+    fn get_samples_per_frame(&self) -> usize;
+
+    /// 起動直後に検出結果を無視するフレーム数
+    fn warmup_frames(&self) -> u64;
+
+    /// 再生中の状態を設定（応答音声による自己検出を抑制する）
+    fn set_speaking_state(&mut self, speaking: SpeakingState);
+}
+
+/// 設定で選択された検出エンジンを生成
+///
+/// # Arguments
+/// * `config` - ウェイクワード設定
+/// * `stt_config` - STT設定（whisperエンジンの認識言語に使う）
+pub fn create_engine(config: &WakewordConfig, stt_config: &SttConfig) -> Result<Box<dyn WakewordEngine>> {
+    match config.engine {
+        WakewordEngineKind::Rustpotter => Ok(Box::new(WakewordDetector::new(config)?)),
+        WakewordEngineKind::Whisper => Ok(Box::new(WhisperSpotter::new(config, stt_config)?)),
+    }
+}
+
+/// 応答再生中の自己検出対策（発話中の状態は`set_speaking_state`で設定）
+pub(super) struct SelfTriggerGuard {
+    mode: SelfTriggerMode,
+    holdoff: Duration,
+    threshold: f32,
+    speaking: Option<SpeakingState>,
+}
+
+impl SelfTriggerGuard {
+    pub fn from_config(config: &WakewordConfig) -> Self {
+        Self {
+            mode: config.self_trigger_mode,
+            holdoff: Duration::from_secs_f32(config.self_trigger_holdoff.max(0.0)),
+            threshold: config.self_trigger_threshold,
+            speaking: None,
+        }
+    }
+
+    /// 再生中の状態を設定
+    pub fn set_speaking_state(&mut self, speaking: SpeakingState) {
+        info!(
+            "自己検出対策: mode={:?}, holdoff={:.2}秒, strict_threshold={}",
+            self.mode,
+            self.holdoff.as_secs_f32(),
+            self.threshold
+        );
+        self.speaking = Some(speaking);
+    }
+
+    /// 再生中（およびholdoff中）の検出を受理するか
+    pub fn accepts(&self, result: &WakewordResult) -> bool {
+        let Some(speaking) = &self.speaking else {
+            return true;
+        };
+        if self.mode == SelfTriggerMode::Off || !speaking.is_speaking_within(self.holdoff) {
+            return true;
+        }
+
+        let accepted = match self.mode {
+            SelfTriggerMode::Strict => result.score >= self.threshold,
+            _ => false,
+        };
+        if !accepted {
+            debug!(
+                "再生中の検出を棄却: keyword=\"{}\", score={:.3} (mode={:?})",
+                result.keyword, result.score, self.mode
+            );
+        }
+        accepted
+    }
+}
+
+/// フレームのRMS（0.0〜1.0）
+pub(super) fn frame_rms(samples: &[i16]) -> f32 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+    let sum: f64 = samples.iter().map(|&s| (s as f64) * (s as f64)).sum();
+    ((sum / samples.len() as f64).sqrt() / i16::MAX as f64) as f32
+}
+
+/// フレームの最小値・最大値
+pub(super) fn frame_amplitude(samples: &[i16]) -> (i16, i16) {
+    let min = samples.iter().min().copied().unwrap_or(0);
+    let max = samples.iter().max().copied().unwrap_or(0);
+    (min, max)
+}
@@ -1,3 +1,13 @@
+mod collector;
 mod detector;
+mod engine;
+mod phrases;
+mod sensitivity;
+mod service;
+mod spotter;
+mod verifier;
 
-pub use detector::{WakewordDetector, WakewordResult};
+pub use collector::{ClipCollector, ClipKind};
+pub use engine::{create_engine, WakewordEngine};
+pub use service::{WakewordEvent, WakewordService};
+pub use verifier::WakewordVerifier;
@@ -0,0 +1,58 @@
+use crate::config::WakewordConfig;
+use crate::text::{fuzzy, kana};
+
+/// 照合対象のフレーズ（元の表記と読みキー）
+pub(super) struct Phrase {
+    pub text: String,
+    key: Vec<char>,
+}
+
+impl Phrase {
+    pub fn new(text: String) -> Self {
+        Self {
+            key: kana::to_reading_key(&text).chars().collect(),
+            text,
+        }
+    }
+}
+
+/// キーワードごとの照合フレーズ（キーワード名, フレーズ一覧）
+///
+/// キーワードごとのphrases → [wakeword.verification]のphrases → キーワード名の順に採用する。
+pub(super) fn keyword_phrases(config: &WakewordConfig) -> Vec<(String, Vec<String>)> {
+    config
+        .entries()
+        .into_iter()
+        .map(|entry| {
+            let key = entry.key();
+            let texts = if !entry.phrases.is_empty() {
+                entry.phrases
+            } else if !config.verification.phrases.is_empty() {
+                config.verification.phrases.clone()
+            } else {
+                vec![key.clone()]
+            };
+            (key, texts)
+        })
+        .collect()
+}
+
+/// 認識結果に最も近いフレーズと類似度（0.0〜1.0）
+///
+/// 読みキーに正規化した上で、認識結果の中の部分一致も考慮する。
+/// 認識結果が空の場合はNone。
+pub(super) fn best_match<'a>(transcript: &str, phrases: &'a [Phrase]) -> Option<(&'a Phrase, f32)> {
+    let transcript_key: Vec<char> = kana::to_reading_key(transcript).chars().collect();
+    if transcript_key.is_empty() {
+        return None;
+    }
+
+    let mut best: Option<(&Phrase, f32)> = None;
+    for phrase in phrases {
+        let window = fuzzy::best_window_match(&transcript_key, &phrase.key);
+        if best.is_none_or(|(_, s)| window.similarity > s) {
+            best = Some((phrase, window.similarity));
+        }
+    }
+    best
+}
@@ -0,0 +1,210 @@
+use anyhow::Result;
+use chrono::{Local, Timelike};
+use log::{debug, info};
+use rustpotter::RustpotterDetection;
+use std::time::{Duration, Instant};
+
+use crate::config::{parse_time_of_day, AutoSensitivityConfig, WakewordConfig};
+
+/// 時間帯スケジュールを確認する間隔
+const SCHEDULE_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+/// ノイズフロアが下がるときの追従係数（静かになったら速やかに追従）
+const NOISE_FLOOR_FALL_ALPHA: f32 = 0.05;
+/// ノイズフロアが上がるときの追従係数（発話で持ち上がらないようゆっくり）
+const NOISE_FLOOR_RISE_ALPHA: f32 = 0.0005;
+/// 自動調整の閾値を変更とみなす最小差（ログの抑制）
+const THRESHOLD_LOG_STEP: f32 = 0.01;
+
+/// 検出に使う閾値の組
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Thresholds {
+    pub threshold: f32,
+    pub avg_threshold: f32,
+    pub min_scores: usize,
+}
+
+/// 時間帯ごとの閾値（開始・終了は0時からの秒数）
+struct Schedule {
+    label: String,
+    start: u32,
+    end: u32,
+    thresholds: Thresholds,
+}
+
+impl Schedule {
+    /// 時刻が時間帯に含まれるか（終了が開始以前なら日付をまたぐ）
+    fn contains(&self, seconds_of_day: u32) -> bool {
+        if self.start < self.end {
+            seconds_of_day >= self.start && seconds_of_day < self.end
+        } else {
+            seconds_of_day >= self.start || seconds_of_day < self.end
+        }
+    }
+}
+
+/// 時間帯スケジュールと環境ノイズによる検出感度の調整
+///
+/// Rustpotterは取りうる最も低い閾値で動かし、検出結果を現在の閾値で絞り込む。
+/// これにより検出器を作り直さずに閾値を変更できる。
+pub(super) struct SensitivityController {
+    base: Thresholds,
+    schedules: Vec<Schedule>,
+    auto: Option<AutoSensitivityConfig>,
+    /// 推定ノイズフロア（前処理前のRMS、0.0〜1.0）
+    noise_floor: Option<f32>,
+    /// 適用中のスケジュール
+    active_schedule: Option<usize>,
+    last_schedule_check: Option<Instant>,
+    current: Thresholds,
+}
+
+impl SensitivityController {
+    /// 設定から生成（スケジュールも自動調整もなければNone）
+    pub fn from_config(config: &WakewordConfig) -> Result<Option<Self>> {
+        let auto = config.auto_sensitivity.enabled.then(|| config.auto_sensitivity.clone());
+        if config.schedules.is_empty() && auto.is_none() {
+            return Ok(None);
+        }
+
+        let base = Thresholds {
+            threshold: config.threshold,
+            avg_threshold: config.avg_threshold,
+            min_scores: config.min_scores,
+        };
+
+        let mut schedules = Vec::with_capacity(config.schedules.len());
+        for schedule in &config.schedules {
+            let start = parse_time_of_day(&schedule.start)?;
+            let end = parse_time_of_day(&schedule.end)?;
+            let thresholds = Thresholds {
+                threshold: schedule.threshold.unwrap_or(base.threshold),
+                avg_threshold: schedule.avg_threshold.unwrap_or(base.avg_threshold),
+                min_scores: schedule.min_scores.unwrap_or(base.min_scores),
+            };
+            info!(
+                "感度スケジュール: {}〜{} threshold={}, avg_threshold={}, min_scores={}",
+                schedule.start, schedule.end, thresholds.threshold, thresholds.avg_threshold, thresholds.min_scores
+            );
+            schedules.push(Schedule {
+                label: format!("{}-{}", schedule.start, schedule.end),
+                start,
+                end,
+                thresholds,
+            });
+        }
+
+        if let Some(auto) = &auto {
+            info!(
+                "自動感度調整: quiet_rms={}, noisy_rms={}, max_boost={}",
+                auto.quiet_rms, auto.noisy_rms, auto.max_boost
+            );
+        }
+
+        Ok(Some(Self {
+            base,
+            schedules,
+            auto,
+            noise_floor: None,
+            active_schedule: None,
+            last_schedule_check: None,
+            current: base,
+        }))
+    }
+
+    /// Rustpotterに設定する閾値（全スケジュールの中で最も低い値）
+    pub fn floor_thresholds(&self) -> Thresholds {
+        self.schedules
+            .iter()
+            .map(|s| s.thresholds)
+            .fold(self.base, |floor, t| Thresholds {
+                threshold: floor.threshold.min(t.threshold),
+                avg_threshold: floor.avg_threshold.min(t.avg_threshold),
+                min_scores: floor.min_scores.min(t.min_scores),
+            })
+    }
+
+    /// 現在適用中の閾値（スケジュール・自動調整を反映済み）
+    pub fn current(&self) -> Thresholds {
+        self.current
+    }
+
+    /// 1フレームごとに呼び出し、ノイズフロアと現在の閾値を更新
+    ///
+    /// # Arguments
+    /// * `raw_rms` - 前処理前のフレームRMS（0.0〜1.0）
+    /// * `in_detection` - 部分検出中か（発話中はノイズフロアを更新しない）
+    pub fn observe(&mut self, raw_rms: f32, in_detection: bool) {
+        if self.auto.is_some() && !in_detection {
+            let floor = self.noise_floor.get_or_insert(raw_rms);
+            let alpha = if raw_rms < *floor {
+                NOISE_FLOOR_FALL_ALPHA
+            } else {
+                NOISE_FLOOR_RISE_ALPHA
+            };
+            *floor += alpha * (raw_rms - *floor);
+        }
+
+        if self
+            .last_schedule_check
+            .is_some_and(|t| t.elapsed() < SCHEDULE_CHECK_INTERVAL)
+        {
+            return;
+        }
+        self.last_schedule_check = Some(Instant::now());
+        self.update_thresholds();
+    }
+
+    /// 検出を現在の閾値で受理するか
+    pub fn accepts(&self, detection: &RustpotterDetection) -> bool {
+        let accepted = detection.score >= self.current.threshold
+            && detection.avg_score >= self.current.avg_threshold
+            && detection.counter >= self.current.min_scores;
+        if !accepted {
+            debug!(
+                "現在の感度で棄却: keyword=\"{}\", score={:.3}, avg_score={:.3}, counter={} (閾値 {:?})",
+                detection.name, detection.score, detection.avg_score, detection.counter, self.current
+            );
+        }
+        accepted
+    }
+
+    /// スケジュールとノイズフロアから現在の閾値を再計算
+    fn update_thresholds(&mut self) {
+        let now = Local::now().time();
+        let seconds_of_day = now.num_seconds_from_midnight();
+        let active = self.schedules.iter().position(|s| s.contains(seconds_of_day));
+        if active != self.active_schedule {
+            match active {
+                Some(i) => info!("感度スケジュール適用: {}", self.schedules[i].label),
+                None => info!("感度スケジュール終了: 基本設定に戻します"),
+            }
+            self.active_schedule = active;
+        }
+
+        let mut thresholds = active.map_or(self.base, |i| self.schedules[i].thresholds);
+        if let (Some(auto), Some(noise_floor)) = (&self.auto, self.noise_floor) {
+            thresholds.threshold = (thresholds.threshold + noise_boost(auto, noise_floor)).min(1.0);
+        }
+
+        if (thresholds.threshold - self.current.threshold).abs() >= THRESHOLD_LOG_STEP
+            || thresholds.avg_threshold != self.current.avg_threshold
+            || thresholds.min_scores != self.current.min_scores
+        {
+            info!(
+                "検出感度を変更: threshold={:.3}, avg_threshold={}, min_scores={} (noise_floor={:.4})",
+                thresholds.threshold,
+                thresholds.avg_threshold,
+                thresholds.min_scores,
+                self.noise_floor.unwrap_or(0.0)
+            );
+        }
+        self.current = thresholds;
+    }
+}
+
+/// ノイズフロアに応じた閾値の上乗せ（quiet_rms以下で0、noisy_rms以上でmax_boost）
+fn noise_boost(auto: &AutoSensitivityConfig, noise_floor: f32) -> f32 {
+    let range = (auto.noisy_rms - auto.quiet_rms).max(f32::EPSILON);
+    let ratio = ((noise_floor - auto.quiet_rms) / range).clamp(0.0, 1.0);
+    ratio * auto.max_boost
+}
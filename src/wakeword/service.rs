@@ -0,0 +1,270 @@
+use anyhow::Result;
+use log::{debug, info, warn};
+use std::collections::VecDeque;
+use std::io::{self, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant, SystemTime};
+
+use super::collector::{ClipCollector, ClipKind};
+use super::engine::WakewordEngine;
+use crate::audio::CaptureReader;
+use crate::cancel::CancelToken;
+
+/// バックグラウンド検出スレッドが発行する検出イベント
+#[derive(Debug, Clone)]
+pub struct WakewordEvent {
+    /// 検出されたウェイクワード名
+    pub keyword: String,
+    /// 検出スコア（0.0〜1.0）
+    pub score: f32,
+    /// 検出時刻（壁時計）
+    pub timestamp: SystemTime,
+    /// 検出時刻（経過時間計測用）
+    pub detected_at: Instant,
+    /// 検出直前の音声（[audio] sample_rate, f32, 最大SEGMENT_SECONDS秒、二段階検証用）
+    pub audio: Vec<f32>,
+}
+
+/// 検出イベントに添付する直前音声の長さ（秒）
+const SEGMENT_SECONDS: f32 = 2.0;
+
+/// ウェイクワード検出をバックグラウンドスレッドで常時実行するサービス
+///
+/// ウォームアップは起動時に一度だけ行い、以降はメインパイプラインが
+/// 処理中でも検出を続けてイベントをチャネルへ送る。
+pub struct WakewordService {
+    events: Receiver<WakewordEvent>,
+    running: Arc<AtomicBool>,
+    show_meter: Arc<AtomicBool>,
+    /// 検出時にキャンセルするトークン（割り込み受付中のみ設定）
+    barge_in: Arc<Mutex<Option<CancelToken>>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl WakewordService {
+    /// 検出器を専用スレッドで起動
+    ///
+    /// `collector`を指定すると、閾値に迫ったが検出に至らなかった音声（near_miss）を保存する。
+    pub fn spawn(
+        mut detector: Box<dyn WakewordEngine>,
+        mut reader: CaptureReader,
+        collector: Option<ClipCollector>,
+    ) -> Result<Self> {
+        let (sender, events) = mpsc::channel();
+        let running = Arc::new(AtomicBool::new(true));
+        let show_meter = Arc::new(AtomicBool::new(true));
+        let barge_in = Arc::new(Mutex::new(None));
+
+        let running_clone = Arc::clone(&running);
+        let show_meter_clone = Arc::clone(&show_meter);
+        let barge_in_clone = Arc::clone(&barge_in);
+
+        let handle = std::thread::Builder::new()
+            .name("wakeword".to_string())
+            .spawn(move || {
+                run_detection_loop(
+                    detector.as_mut(),
+                    &mut reader,
+                    &sender,
+                    &running_clone,
+                    &show_meter_clone,
+                    &barge_in_clone,
+                    collector.as_ref(),
+                );
+                debug!("ウェイクワード検出スレッドを終了しました");
+            })?;
+
+        info!("ウェイクワード検出スレッドを起動しました");
+
+        Ok(Self {
+            events,
+            running,
+            show_meter,
+            barge_in,
+            handle: Some(handle),
+        })
+    }
+
+    /// 次の検出イベントを待機
+    pub fn recv(&self) -> Result<WakewordEvent> {
+        self.events
+            .recv()
+            .map_err(|_| anyhow::anyhow!("ウェイクワード検出スレッドが停止しています"))
+    }
+
+    /// 指定時間まで検出イベントを待機（タイムアウト時はNone）
+    pub fn recv_timeout(&self, timeout: Duration) -> Result<Option<WakewordEvent>> {
+        match self.events.recv_timeout(timeout) {
+            Ok(event) => Ok(Some(event)),
+            Err(RecvTimeoutError::Timeout) => Ok(None),
+            Err(RecvTimeoutError::Disconnected) => {
+                Err(anyhow::anyhow!("ウェイクワード検出スレッドが停止しています"))
+            }
+        }
+    }
+
+    /// 溜まっている検出イベントを全て取り出す
+    pub fn drain(&self) -> Vec<WakewordEvent> {
+        self.events.try_iter().collect()
+    }
+
+    /// 待機中のレベルメーター表示の有無を切り替え（処理中は表示を止める）
+    pub fn set_meter(&self, enabled: bool) {
+        self.show_meter.store(enabled, Ordering::Relaxed);
+    }
+
+    /// 割り込み（バージイン）の受付を設定
+    ///
+    /// トークンを設定している間に検出があると、イベント送信後にキャンセルする。
+    /// Noneで受付を解除する。
+    pub fn set_barge_in(&self, token: Option<CancelToken>) {
+        *self.barge_in.lock().unwrap() = token;
+    }
+
+    /// 検出スレッドを停止
+    pub fn stop(&mut self) {
+        self.running.store(false, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            if handle.join().is_err() {
+                warn!("ウェイクワード検出スレッドが異常終了しました");
+            }
+        }
+    }
+}
+
+impl Drop for WakewordService {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// 検出ループ本体（検出スレッドで実行）
+fn run_detection_loop(
+    detector: &mut dyn WakewordEngine,
+    reader: &mut CaptureReader,
+    sender: &Sender<WakewordEvent>,
+    running: &AtomicBool,
+    show_meter: &AtomicBool,
+    barge_in: &Mutex<Option<CancelToken>>,
+    collector: Option<&ClipCollector>,
+) {
+    let samples_per_frame = detector.get_samples_per_frame();
+    let warmup_frames = detector.warmup_frames();
+    reader.reset();
+
+    // ウォームアップ（起動時に一度だけ）
+    for frame_count in 1..=warmup_frames {
+        if !running.load(Ordering::Relaxed) {
+            return;
+        }
+        if frame_count == 1 || frame_count % 10 == 0 {
+            print!("\r  [Warming up] frames:{}/{}    ", frame_count, warmup_frames);
+            let _ = io::stdout().flush();
+        }
+        // 検出器の内部状態を更新するが、検出結果は無視
+        let raw_samples = reader.read_frame(samples_per_frame);
+        let _ = detector.process_frame(&raw_samples);
+    }
+    println!();
+    println!("  [Ready] Say the wakeword!");
+
+    let mut frame_index = 0u64;
+    let mut max_rms_seen: f32 = 0.0;
+    let mut max_score_seen: f32 = 0.0;
+
+    // 検出直前の音声を保持するリングバッファ（前処理前の生音声）
+    let segment_capacity = (SEGMENT_SECONDS * reader.target_sample_rate() as f32) as usize;
+    let mut segment: VecDeque<i16> = VecDeque::with_capacity(segment_capacity + samples_per_frame);
+
+    let mut near_miss_tracker = collector.map(ClipCollector::near_miss_tracker);
+
+    while running.load(Ordering::Relaxed) {
+        frame_index += 1;
+
+        let raw_samples = reader.read_frame(samples_per_frame);
+        segment.extend(raw_samples.iter().copied());
+        while segment.len() > segment_capacity {
+            segment.pop_front();
+        }
+
+        let frame = detector.process_frame(&raw_samples);
+
+        // 最大値を追跡（診断用）
+        if frame.rms > max_rms_seen {
+            max_rms_seen = frame.rms;
+        }
+        if frame.partial_score > max_score_seen {
+            max_score_seen = frame.partial_score;
+        }
+
+        if show_meter.load(Ordering::Relaxed) {
+            let (sample_min, sample_max) = frame.amplitude;
+            print!(
+                "\r  [Listening] rms:{:.4} (max:{:.4}) score:{:.3} (max:{:.3}) amp:[{},{}]    ",
+                frame.rms, max_rms_seen, frame.partial_score, max_score_seen, sample_min, sample_max
+            );
+            let _ = io::stdout().flush();
+        }
+
+        // 感度スケジュール等で変わった閾値をクリップのメタデータに反映
+        if let (Some(thresholds), Some(collector)) = (frame.thresholds, collector) {
+            collector.set_thresholds(thresholds);
+        }
+
+        // 閾値に迫ったが検出に至らなかった音声を保存
+        if let (Some(tracker), Some(collector)) = (near_miss_tracker.as_mut(), collector) {
+            if let Some(near_miss) = tracker.update(&frame) {
+                let audio = segment_to_f32(&segment);
+                if let Err(e) = collector.save(
+                    ClipKind::NearMiss,
+                    &near_miss.keyword,
+                    near_miss.peak_score,
+                    &audio,
+                    SystemTime::now(),
+                    None,
+                ) {
+                    warn!("near_missクリップの保存に失敗: {:#}", e);
+                }
+            }
+        }
+
+        let Some(result) = frame.detection else {
+            continue;
+        };
+
+        info!(
+            "ウェイクワード検出 ({}): keyword=\"{}\", score={:.3}, frame={}",
+            detector.name(),
+            result.keyword,
+            result.score,
+            frame_index
+        );
+
+        let event = WakewordEvent {
+            keyword: result.keyword,
+            score: result.score,
+            timestamp: SystemTime::now(),
+            detected_at: Instant::now(),
+            audio: segment_to_f32(&segment),
+        };
+
+        if sender.send(event).is_err() {
+            // 受信側が破棄された
+            break;
+        }
+
+        // 応答中なら割り込み（イベント送信後にキャンセルし、受信側で取り出せるようにする）
+        if let Some(token) = barge_in.lock().unwrap().as_ref() {
+            info!("割り込み検出: 応答を中断します");
+            token.cancel();
+        }
+    }
+}
+
+/// 直前音声のバッファをf32に変換
+fn segment_to_f32(segment: &VecDeque<i16>) -> Vec<f32> {
+    segment.iter().map(|&s| s as f32 / i16::MAX as f32).collect()
+}
@@ -0,0 +1,252 @@
+use anyhow::Result;
+use log::{debug, info, warn};
+use std::collections::VecDeque;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::time::Instant;
+
+use super::engine::{frame_amplitude, frame_rms, FrameResult, SelfTriggerGuard, WakewordEngine, WakewordResult};
+use super::phrases::{self, Phrase};
+use crate::audio::SpeakingState;
+use crate::config::{SttConfig, WakewordConfig};
+use crate::stt::{SpeechToText, WhisperStt};
+
+/// スポッターの1フレームのサンプル数（30ms @ 16kHz）
+const SPOTTER_FRAME_SAMPLES: usize = 480;
+/// 入力サンプルレート
+const SAMPLE_RATE: f32 = 16000.0;
+
+/// 小型Whisperモデルによるスライディングウィンドウのフレーズ検出器
+///
+/// 直近`window_seconds`秒の音声を`hop_seconds`ごとに認識し、
+/// ウェイクワードのフレーズと読みであいまい一致したら検出とする。
+/// 認識は専用のワーカースレッドで行い、検出スレッド（音声の読み取り）は待たせない。
+/// 認識が`hop_seconds`に間に合わない場合は、前の認識が終わり次第その時点の区間を認識する。
+/// そのため発話の終わりから検出までの遅れは、最大でhop_seconds + 認識2回分になる。
+pub struct WhisperSpotter {
+    /// 認識ワーカーへ区間の音声を送る
+    jobs: Sender<Vec<f32>>,
+    /// 認識ワーカーからの結果（最も近いキーワードと類似度）
+    results: Receiver<Option<(String, f32)>>,
+    /// 認識ワーカーが処理中か
+    decoding: bool,
+    /// 直近の音声（最大window_samples）
+    window: VecDeque<i16>,
+    window_samples: usize,
+    hop_samples: usize,
+    /// 前回の認識から受け取ったサンプル数
+    samples_since_decode: usize,
+    /// 前回の認識以降の最大RMS
+    hop_peak_rms: f32,
+    min_similarity: f32,
+    min_rms: f32,
+    /// 直近の認識で最も近かったキーワードと類似度
+    last_partial: Option<(String, f32)>,
+    /// 応答再生中の自己検出対策
+    self_trigger: SelfTriggerGuard,
+}
+
+impl WhisperSpotter {
+    /// 設定からWhisperSpotterを生成し、認識ワーカーを起動
+    ///
+    /// # Arguments
+    /// * `config` - ウェイクワード設定（`[wakeword.whisper_spotter]`を使用）
+    /// * `stt_config` - STT設定（モデルパス以外の認識設定を引き継ぐ）
+    pub fn new(config: &WakewordConfig, stt_config: &SttConfig) -> Result<Self> {
+        let spotter = &config.whisper_spotter;
+        if spotter.hop_seconds <= 0.0 || spotter.window_seconds < spotter.hop_seconds {
+            return Err(anyhow::anyhow!(
+                "[wakeword.whisper_spotter] の設定が不正です: window_seconds={} は hop_seconds={} (> 0) 以上にしてください",
+                spotter.window_seconds,
+                spotter.hop_seconds
+            ));
+        }
+
+        let keyword_phrases = phrases::keyword_phrases(config);
+        if keyword_phrases.is_empty() {
+            return Err(anyhow::anyhow!(
+                "ウェイクワードが設定されていません（wakeword_path または keywords を指定してください）"
+            ));
+        }
+        if !config.schedules.is_empty() || config.auto_sensitivity.enabled {
+            warn!("時間帯・自動感度調整はRustpotterエンジン専用のため、whisperエンジンでは無視します");
+        }
+
+        // 語彙補正はコマンド認識用のため使わない（プロンプトはフレーズから生成する）
+        // 最初の認識はワーカー上で行われ検出スレッドを止めないため、ウォームアップは省く
+        let stt = WhisperStt::new(&SttConfig {
+            model_path: spotter.model_path.clone(),
+            initial_prompt: None,
+            phrases_path: None,
+            warmup: false,
+            ..stt_config.clone()
+        })?;
+
+        let prompt = keyword_phrases
+            .iter()
+            .flat_map(|(_, texts)| texts.iter().map(String::as_str))
+            .collect::<Vec<_>>()
+            .join("、");
+
+        for (key, texts) in &keyword_phrases {
+            info!("Whisperスポッター: keyword=\"{}\", phrases={:?}", key, texts);
+        }
+
+        let decoder = SpotterDecoder {
+            stt,
+            phrases: keyword_phrases
+                .into_iter()
+                .map(|(key, texts)| (key, texts.into_iter().map(Phrase::new).collect()))
+                .collect(),
+            prompt,
+        };
+        let (jobs, job_receiver) = mpsc::channel::<Vec<f32>>();
+        let (result_sender, results) = mpsc::channel();
+        std::thread::Builder::new()
+            .name("wakeword-spotter".to_string())
+            .spawn(move || {
+                // WhisperSpotterが破棄されるとjobsが閉じて終了する
+                for audio in job_receiver {
+                    let best = decoder.decode(&audio).unwrap_or_else(|e| {
+                        warn!("Whisperスポッターの認識に失敗: {:#}", e);
+                        None
+                    });
+                    if result_sender.send(best).is_err() {
+                        break;
+                    }
+                }
+                debug!("Whisperスポッターの認識スレッドを終了しました");
+            })?;
+
+        info!(
+            "Whisperスポッター初期化完了: window={:.2}秒, hop={:.2}秒, min_similarity={:.2}, min_rms={}",
+            spotter.window_seconds, spotter.hop_seconds, spotter.min_similarity, spotter.min_rms
+        );
+
+        let window_samples = (spotter.window_seconds * SAMPLE_RATE) as usize;
+        Ok(Self {
+            jobs,
+            results,
+            decoding: false,
+            window: VecDeque::with_capacity(window_samples + SPOTTER_FRAME_SAMPLES),
+            window_samples,
+            hop_samples: (spotter.hop_seconds * SAMPLE_RATE) as usize,
+            samples_since_decode: 0,
+            hop_peak_rms: 0.0,
+            min_similarity: spotter.min_similarity,
+            min_rms: spotter.min_rms,
+            last_partial: None,
+            self_trigger: SelfTriggerGuard::from_config(config),
+        })
+    }
+}
+
+/// 区間の認識とフレーズ照合（認識ワーカーで実行）
+struct SpotterDecoder {
+    stt: WhisperStt,
+    /// キーワードごとの照合フレーズ
+    phrases: Vec<(String, Vec<Phrase>)>,
+    /// 認識時に与える初期プロンプト（全フレーズを列挙）
+    prompt: String,
+}
+
+impl SpotterDecoder {
+    /// 区間を認識し、最も近いキーワードと類似度を返す
+    fn decode(&self, audio: &[f32]) -> Result<Option<(String, f32)>> {
+        let start = Instant::now();
+        let transcript = self.stt.transcribe_with_prompt(audio, Some(&self.prompt))?.text;
+
+        let best = self
+            .phrases
+            .iter()
+            .filter_map(|(key, candidates)| {
+                phrases::best_match(&transcript, candidates).map(|(_, similarity)| (key.clone(), similarity))
+            })
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+
+        debug!(
+            "Whisperスポッター: transcript=\"{}\" best={:?} ({:.0}ms)",
+            transcript,
+            best,
+            start.elapsed().as_secs_f32() * 1000.0
+        );
+        Ok(best)
+    }
+}
+
+impl WakewordEngine for WhisperSpotter {
+    fn name(&self) -> &'static str {
+        "Whisper"
+    }
+
+    fn process_frame(&mut self, raw_samples: &[i16]) -> FrameResult {
+        let rms = frame_rms(raw_samples);
+        let amplitude = frame_amplitude(raw_samples);
+
+        self.window.extend(raw_samples.iter().copied());
+        while self.window.len() > self.window_samples {
+            self.window.pop_front();
+        }
+        self.samples_since_decode += raw_samples.len();
+        self.hop_peak_rms = self.hop_peak_rms.max(rms);
+
+        // 認識ワーカーの結果を受け取る
+        let mut detection = None;
+        if let Ok(best) = self.results.try_recv() {
+            self.decoding = false;
+            self.last_partial = best;
+            if let Some((keyword, score)) = self.last_partial.clone() {
+                if score >= self.min_similarity {
+                    detection = Some(WakewordResult { keyword, score });
+                    // 同じ発話を次の区間で再検出しないよう、区間を空にする
+                    self.window.clear();
+                    self.samples_since_decode = 0;
+                    self.hop_peak_rms = 0.0;
+                }
+            }
+        }
+
+        // 認識中は次の区間を送らない（終わり次第、その時点の区間を送る）
+        if !self.decoding
+            && self.samples_since_decode >= self.hop_samples
+            && self.window.len() >= self.window_samples
+        {
+            let voiced = self.hop_peak_rms >= self.min_rms;
+            self.samples_since_decode = 0;
+            self.hop_peak_rms = 0.0;
+
+            // 直近に音声がなければ認識しない（無音でのハルシネーションと負荷を避ける）
+            if voiced {
+                let audio: Vec<f32> = self.window.iter().map(|&s| s as f32 / i16::MAX as f32).collect();
+                self.decoding = self.jobs.send(audio).is_ok();
+                if !self.decoding {
+                    warn!("Whisperスポッターの認識スレッドが停止しています");
+                }
+            } else {
+                self.last_partial = None;
+            }
+        }
+
+        let detection = detection.filter(|result| self.self_trigger.accepts(result));
+        FrameResult {
+            detection,
+            partial_score: self.last_partial.as_ref().map(|(_, s)| *s).unwrap_or(0.0),
+            partial_keyword: self.last_partial.as_ref().map(|(k, _)| k.clone()),
+            rms,
+            amplitude,
+            thresholds: None,
+        }
+    }
+
+    fn get_samples_per_frame(&self) -> usize {
+        SPOTTER_FRAME_SAMPLES
+    }
+
+    /// 区間が埋まるまで（それまでは認識を行わない）
+    fn warmup_frames(&self) -> u64 {
+        (self.window_samples / SPOTTER_FRAME_SAMPLES) as u64
+    }
+
+    fn set_speaking_state(&mut self, speaking: SpeakingState) {
+        self.self_trigger.set_speaking_state(speaking);
+    }
+}
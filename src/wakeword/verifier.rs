@@ -0,0 +1,93 @@
+use anyhow::Result;
+use log::{debug, info};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use super::phrases::{self, Phrase};
+use super::service::WakewordEvent;
+use crate::config::WakewordConfig;
+use crate::stt::SpeechToText;
+
+/// 二段階検証の判定結果
+#[derive(Debug, Clone)]
+pub struct VerifierDecision {
+    /// 受理したか
+    pub accepted: bool,
+    /// Whisperの認識結果
+    pub transcript: String,
+    /// 最も近かったフレーズ
+    pub matched_phrase: Option<String>,
+    /// 最も近かったフレーズとの類似度（0.0〜1.0）
+    pub similarity: f32,
+    /// 検証に要した時間
+    pub latency: Duration,
+}
+
+/// Rustpotterの検出をWhisperで再確認する検証器
+///
+/// 検出直前の音声を短いプロンプト付きで認識し、設定されたフレーズと
+/// かな/ローマ字を正規化した読みであいまい一致した場合のみ受理する。
+pub struct WakewordVerifier {
+    phrases: HashMap<String, Vec<Phrase>>,
+    prompts: HashMap<String, String>,
+    min_similarity: f32,
+}
+
+impl WakewordVerifier {
+    /// 設定から検証器を生成（検証が無効ならNone）
+    pub fn from_config(config: &WakewordConfig) -> Option<Self> {
+        let verification = &config.verification;
+        if !verification.enabled {
+            return None;
+        }
+
+        let mut phrase_table = HashMap::new();
+        let mut prompts = HashMap::new();
+        for (key, texts) in phrases::keyword_phrases(config) {
+            let prompt = verification
+                .prompt
+                .clone()
+                .unwrap_or_else(|| texts.join("、"));
+
+            info!(
+                "ウェイクワード検証フレーズ: keyword=\"{}\", phrases={:?}, min_similarity={:.2}",
+                key, texts, verification.min_similarity
+            );
+
+            phrase_table.insert(key.clone(), texts.into_iter().map(Phrase::new).collect());
+            prompts.insert(key, prompt);
+        }
+
+        Some(Self {
+            phrases: phrase_table,
+            prompts,
+            min_similarity: verification.min_similarity,
+        })
+    }
+
+    /// 検出イベントの音声をWhisperで認識し、フレーズと照合する
+    pub fn verify(&self, stt: &dyn SpeechToText, event: &WakewordEvent) -> Result<VerifierDecision> {
+        let start = Instant::now();
+        let prompt = self.prompts.get(&event.keyword).map(String::as_str);
+        let transcript = stt.transcribe_with_prompt(&event.audio, prompt)?.text;
+
+        let best = self
+            .phrases
+            .get(&event.keyword)
+            .and_then(|candidates| phrases::best_match(&transcript, candidates));
+        debug!(
+            "検証照合: transcript=\"{}\" best={:?}",
+            transcript,
+            best.map(|(p, s)| (p.text.as_str(), s))
+        );
+
+        let similarity = best.map(|(_, s)| s).unwrap_or(0.0);
+        Ok(VerifierDecision {
+            accepted: best.is_some() && similarity >= self.min_similarity,
+            transcript,
+            matched_phrase: best.map(|(p, _)| p.text.clone()),
+            similarity,
+            latency: start.elapsed(),
+        })
+    }
+}
@@ -73,6 +73,6 @@ echo.
 echo [3/3] Starting Smart Speaker...
 echo.
 cd /d "%PROJECT_DIR%"
-cargo run --release
+cargo run --release --features cuda
 
 pause